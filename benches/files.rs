@@ -0,0 +1,71 @@
+use chrono::{Datelike, Days, NaiveDate};
+use myw::{Context, Log};
+use std::{env, fmt::Write, fs, path::PathBuf, sync::LazyLock, thread};
+
+/// Two years of generated weekly logs, with eight entries on every weekday.
+static CORPUS: LazyLock<Vec<PathBuf>> = LazyLock::new(|| generate(2022, 2));
+
+fn main() {
+    divan::main();
+}
+
+/// Writes a log per ISO week to a temporary directory, replacing those of earlier runs, and
+/// returns their paths.
+fn generate(year: i32, years: i32) -> Vec<PathBuf> {
+    let directory = env::temp_dir().join("myw-bench-files");
+    fs::create_dir_all(&directory).unwrap();
+    let projects = ["ABC", "DEF", "GHI", "\"Client X\"", "LUNCH"];
+    let mut paths = vec![];
+    let mut monday = NaiveDate::from_isoywd_opt(year, 1, chrono::Weekday::Mon).unwrap();
+    while monday.iso_week().year() < year + years {
+        let week = monday.iso_week();
+        let mut content = format!("# {}-W{:02}\n", week.year(), week.week());
+        for (day, date) in monday.iter_days().take(5).enumerate() {
+            writeln!(content, "\n## {}\n", date.format("%A")).unwrap();
+            for hour in 8..16 {
+                let project = projects[(hour + day) % projects.len()];
+                writeln!(
+                    content,
+                    "- {}:00-{}:45 {} worked on *item* {} #tag{}",
+                    hour,
+                    hour,
+                    project,
+                    hour * day,
+                    day
+                )
+                .unwrap();
+            }
+        }
+        let path = directory.join(format!("{}-w{:02}.md", week.year(), week.week()));
+        fs::write(&path, content).unwrap();
+        paths.push(path);
+        monday = monday + Days::new(7);
+    }
+    paths
+}
+
+#[divan::bench(args = [1, 2, 4, 8])]
+fn bench_parse_files(bencher: divan::Bencher, threads: usize) {
+    let paths = &*CORPUS;
+
+    bencher.bench_local(move || {
+        // A share of the files per thread, like `myw` parses the logs it reads.
+        let chunk = paths.len().div_ceil(threads);
+        thread::scope(|scope| {
+            let workers = paths
+                .chunks(chunk)
+                .map(|paths| scope.spawn(move || paths.iter().map(parse).collect::<Vec<_>>()))
+                .collect::<Vec<_>>();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .flatten()
+                .collect::<Log>()
+        })
+    });
+}
+
+fn parse(path: &PathBuf) -> Log {
+    let content = fs::read_to_string(path).unwrap();
+    Log::parse(&content, &Context::from_path(path)).unwrap()
+}
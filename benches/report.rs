@@ -1,7 +1,4 @@
-use myw::{
-    report::{Fill, Report},
-    timelog::Log,
-};
+use myw::{Context, Log, Report};
 use std::{fs, path::PathBuf};
 
 fn main() {
@@ -13,10 +10,10 @@ fn bench_report_by_date_short(bencher: divan::Bencher) {
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     path.push("resources/bench_short.md");
     let content = fs::read_to_string(&path).unwrap();
-    let log = Log::parse(&content);
+    let log = Log::parse(&content, &Context::default()).unwrap();
 
     bencher.bench_local(move || {
-        Report::by_date_by_project(&log, Fill::Padded);
+        Report::builder(&log).build();
     });
 }
 
@@ -25,10 +22,10 @@ fn bench_report_by_date_long(bencher: divan::Bencher) {
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     path.push("resources/bench_long.md");
     let content = fs::read_to_string(&path).unwrap();
-    let log = Log::parse(&content);
+    let log = Log::parse(&content, &Context::default()).unwrap();
 
     bencher.bench_local(move || {
-        Report::by_date_by_project(&log, Fill::Padded);
+        Report::builder(&log).build();
     });
 }
 
@@ -37,9 +34,9 @@ fn bench_report_by_date_scrambled(bencher: divan::Bencher) {
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     path.push("resources/bench_long_scrambled.md");
     let content = fs::read_to_string(&path).unwrap();
-    let log = Log::parse(&content);
+    let log = Log::parse(&content, &Context::default()).unwrap();
 
     bencher.bench_local(move || {
-        Report::by_date_by_project(&log, Fill::Padded);
+        Report::builder(&log).build();
     });
 }
@@ -1,4 +1,6 @@
-use myw::timelog::Log;
+#[cfg(feature = "cache")]
+use myw::Cache;
+use myw::{Context, Log, Reader};
 use std::{fs, path::PathBuf};
 
 fn main() {
@@ -12,7 +14,7 @@ fn bench_parse_timelog_short(bencher: divan::Bencher) {
     let content = fs::read_to_string(&path).unwrap();
 
     bencher.bench_local(move || {
-        Log::parse(&content);
+        Log::parse(&content, &Context::default()).unwrap();
     });
 }
 
@@ -23,7 +25,22 @@ fn bench_parse_timelog_long(bencher: divan::Bencher) {
     let content = fs::read_to_string(&path).unwrap();
 
     bencher.bench_local(move || {
-        Log::parse(&content);
+        Log::parse(&content, &Context::default()).unwrap();
+    });
+}
+
+#[divan::bench(args = [Reader::Markdown, Reader::Lines, Reader::PlainLines])]
+fn bench_read_timelog_long(bencher: divan::Bencher, reader: Reader) {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("resources/bench_long.md");
+    let content = fs::read_to_string(&path).unwrap();
+    let context = Context {
+        reader,
+        ..Context::default()
+    };
+
+    bencher.bench_local(move || {
+        Log::parse(&content, &context).unwrap();
     });
 }
 
@@ -34,6 +51,34 @@ fn bench_parse_timelog_long_scrambled(bencher: divan::Bencher) {
     let content = fs::read_to_string(&path).unwrap();
 
     bencher.bench_local(move || {
-        Log::parse(&content);
+        Log::parse(&content, &Context::default()).unwrap();
+    });
+}
+
+/// Parses through the cache after clearing it, so every iteration parses and stores the log.
+#[cfg(feature = "cache")]
+#[divan::bench]
+fn bench_parse_timelog_long_cache_cold(bencher: divan::Bencher) {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("resources/bench_long.md");
+    let cache = Cache::new(std::env::temp_dir().join("myw-bench-cache-cold"));
+
+    bencher.bench_local(move || {
+        cache.clear().unwrap();
+        cache.parse(&path, &Context::default()).unwrap();
+    });
+}
+
+/// Parses through a cache that already has the log, so every iteration reads it from there.
+#[cfg(feature = "cache")]
+#[divan::bench]
+fn bench_parse_timelog_long_cache_warm(bencher: divan::Bencher) {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("resources/bench_long.md");
+    let cache = Cache::new(std::env::temp_dir().join("myw-bench-cache-warm"));
+    cache.parse(&path, &Context::default()).unwrap();
+
+    bencher.bench_local(move || {
+        cache.parse(&path, &Context::default()).unwrap();
     });
 }
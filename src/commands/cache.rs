@@ -0,0 +1,25 @@
+use crate::{timelog::Cache, Error};
+use clap::Command;
+
+pub fn define() -> Command {
+    Command::new("cache")
+        .about("Manage the cache of parsed logs, which are only parsed again when they change")
+        .subcommand_required(true)
+        .subcommand(Command::new("clear").about("Remove all cached logs"))
+        .subcommand(Command::new("dir").about("Print the directory of the cache"))
+}
+
+pub fn run(matches: &clap::ArgMatches) -> Result<(), Error> {
+    let directory = Cache::default_directory().ok_or_else(|| {
+        Error::Argument("no cache directory, set $MYW_CACHE_DIR or $HOME".to_owned())
+    })?;
+    match matches.subcommand() {
+        Some(("clear", _)) => {
+            let count = Cache::new(directory).clear()?;
+            super::print(&format!("removed {} cached logs\n", count))
+        }
+        Some(("dir", _)) => super::print(&format!("{}\n", directory.display())),
+        Some((name, _)) => Err(Error::Argument(format!("unknown cache command {}", name))),
+        None => Ok(()),
+    }
+}
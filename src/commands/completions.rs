@@ -0,0 +1,18 @@
+use crate::Error;
+use clap::{arg, Command};
+use clap_complete::Shell;
+
+pub fn define() -> Command {
+    Command::new("completions")
+        .about("Print a completion script for a shell")
+        .arg(arg!(<shell> "Shell to complete in").value_parser(clap::value_parser!(Shell)))
+}
+
+pub fn run(matches: &clap::ArgMatches) -> Result<(), Error> {
+    let shell = *matches.get_one::<Shell>("shell").unwrap();
+    let mut command = super::cli();
+    let name = command.get_name().to_owned();
+    let mut script = vec![];
+    clap_complete::generate(shell, &mut command, name, &mut script);
+    super::print(&String::from_utf8_lossy(&script))
+}
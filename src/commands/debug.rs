@@ -1,6 +1,7 @@
-use crate::timelog;
+use crate::config::Config;
+use crate::Error;
 use clap::{arg, Command};
-use std::{error::Error, fs, path::PathBuf};
+use std::path::PathBuf;
 
 pub fn define() -> Command {
     Command::new("debug")
@@ -11,14 +12,15 @@ pub fn define() -> Command {
                 .required(true)
                 .value_parser(super::parse_file_path),
         )
+        .arg(super::depth_arg())
 }
 
-pub fn run(matches: &clap::ArgMatches) -> Result<(), Box<dyn Error>> {
+pub fn run(matches: &clap::ArgMatches, config: &Config) -> Result<(), Error> {
     let file = matches.get_one::<PathBuf>("file").unwrap();
-    let content = fs::read_to_string(file)?;
-    let timelog::Log(result) = timelog::Log::parse(&content);
-    for entry in result {
-        println!("{}", entry);
-    }
-    Ok(())
+    let log = super::read_log(file, matches, config)?;
+    let output = log
+        .iter()
+        .map(|entry| format!("{}\n", entry))
+        .collect::<String>();
+    super::print(&output)
 }
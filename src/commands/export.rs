@@ -0,0 +1,63 @@
+use crate::{config::Config, formats, utils::DateRange, Error};
+use clap::{arg, Command};
+use colored::Colorize;
+use std::{fs, path::PathBuf};
+
+pub fn define() -> Command {
+    Command::new("export")
+        .about("Export entries to other formats")
+        .arg(arg!(<files> ... "Paths to the files to export").value_parser(super::parse_file_path))
+        .arg(
+            arg!(-f --format <format> "Format to export to")
+                .value_parser(["ics", "timeclock", "timewarrior"])
+                .default_value("ics"),
+        )
+        .arg(super::range_arg())
+        .arg(
+            arg!(-o --output <file> "File to write to [default: stdout]")
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+        .arg(super::depth_arg())
+}
+
+pub fn run(matches: &clap::ArgMatches, config: &Config) -> Result<(), Error> {
+    let files = matches
+        .get_many::<PathBuf>("files")
+        .unwrap()
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut log = super::read_log_files(&files, matches, config)?;
+    if let Some(range) = matches.get_one::<DateRange>("range") {
+        log = log.within(range);
+    }
+    let format = matches.get_one::<String>("format").unwrap().as_str();
+    let without_clock_times = log.iter().filter(|entry| entry.from().is_none()).count();
+    match format {
+        "timeclock" if without_clock_times > 0 => eprintln!(
+            "{}: {} entries without clock times are exported to timeclock as comments",
+            "warning".yellow().bold(),
+            without_clock_times
+        ),
+        "timewarrior" => {
+            let skipped = formats::timewarrior::unexportable(&log, &chrono::Local);
+            if skipped > 0 {
+                eprintln!(
+                    "{}: {} entries without clock times, or with times that don't exist in the \
+                     local time zone, can't be exported to timewarrior",
+                    "warning".yellow().bold(),
+                    skipped
+                );
+            }
+        }
+        _ => {}
+    }
+    let output = match format {
+        "timeclock" => formats::timeclock::write(&log),
+        "timewarrior" => formats::timewarrior::write(&log, &chrono::Local),
+        _ => formats::ics::write(&log, chrono::Utc::now()),
+    };
+    match matches.get_one::<PathBuf>("output") {
+        Some(path) => fs::write(path, output).map_err(|err| Error::io(path, err)),
+        None => super::print(&output),
+    }
+}
@@ -0,0 +1,82 @@
+use crate::Error;
+use clap::{arg, Command};
+use colored::Colorize;
+
+/// The syntax of logs, as sections with a title and paragraphs. Indented lines are examples.
+pub static SYNTAX: [(&str, &str); 3] = [
+    (
+        "ENTRIES",
+        "\
+An entry is a list item with a time and a project, optionally followed by notes:
+
+    - 9:00-10:30 ABC fixed the build #ci
+
+The time is one of:
+
+    9-10:30, 0900-1030, 9:00 - 10:30     from and until
+    9am-1:30pm, 1-3pm, 9 a.m. - 1 p.m.   from and until, on a 12-hour clock
+    9:00 +45m, 13:30 + 1h30              a start and a duration
+    1h30, 1.5h, 2 hours, 90m, 90 min     a duration without clock times
+    all day, all-day                     a day off, like leave
+
+A colon may separate the time from the project. Projects are words of at least three letters, \
+digits or underscores, or anything in double quotes (\"Client X\"). Hashtags in the notes are \
+tags. Entries in block quotes or sub-lists are only read up to the nesting given with --depth.",
+    ),
+    (
+        "HEADINGS",
+        "\
+Entries belong to the date in the closest heading above them. A heading ends the scope of date \
+headings at the same or a higher level. Dates are written as yyyy-mm-dd anywhere in the \
+heading, or in the formats and locales from the configuration. A heading with just a weekday \
+refers to that day in the date range of the file. A reason for a day off in parentheses, one \
+of vacation, holiday, leave, sick, sick leave, day off or pto, marks the day as leave:
+
+    ## 2024-02-13
+    ## Tuesday
+    ## Tue 13 Feb 2024
+    ## 2024-02-14 (vacation)",
+    ),
+    (
+        "DATE RANGES",
+        "\
+Ranges on the command line are a day, an ISO week, a month, a year or the dates between two \
+dates. File names imply the range of the log in them the same way, ignoring any other text \
+around the date, except for ranges between two dates:
+
+    2024-02-13               a day
+    2024-w07                 an ISO week, also 2024W07
+    2024-02                  a month
+    2024                     a year
+    2024-02-12..2024-02-18   the first and last date",
+    ),
+];
+
+pub fn define() -> Command {
+    Command::new("help")
+        .about("Print help for a command, or about the syntax of logs with `myw help syntax`")
+        .arg(arg!([topic] "Command or topic (syntax)"))
+}
+
+pub fn run(matches: &clap::ArgMatches) -> Result<(), Error> {
+    let mut command = super::cli();
+    command.build();
+    let help = match matches.get_one::<String>("topic").map(String::as_str) {
+        Some("syntax") => return super::print(&syntax()),
+        Some(topic) => command
+            .find_subcommand_mut(topic)
+            .ok_or_else(|| Error::Argument(format!("no help topic or command {}", topic)))?
+            .render_long_help(),
+        None => command.render_long_help(),
+    };
+    let help = help.ansi().to_string();
+    super::print(&help)
+}
+
+fn syntax() -> String {
+    SYNTAX
+        .iter()
+        .map(|(title, body)| format!("{}\n{}\n", title.bold(), body))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,88 @@
+use crate::{config::Config, formats, timelog, Error};
+use clap::{arg, Command};
+use std::{fs, path::PathBuf};
+
+pub fn define() -> Command {
+    Command::new("import")
+        .about("Import entries from other time trackers into week files")
+        .arg(
+            arg!(<files> ... "Exported files (Toggl or Clockify CSV, timewarrior data, iCalendar)")
+                .value_parser(super::parse_file_path),
+        )
+        .arg(
+            arg!(-f --from <format> "Format of the files [default: detected from the extension]")
+                .value_parser(["csv", "toggl", "clockify", "timewarrior", "ics"]),
+        )
+        .arg(
+            arg!(--dir <directory> "Directory with week files [default: from config]")
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+        .arg(arg!(-n --"dry-run" "Report what would be imported without writing files"))
+}
+
+pub fn run(matches: &clap::ArgMatches, config: &Config) -> Result<(), Error> {
+    let mut entries = vec![];
+    for file in matches.get_many::<PathBuf>("files").unwrap() {
+        let format = match matches.get_one::<String>("from") {
+            Some(format) => format.as_str(),
+            None => match file.extension().and_then(|e| e.to_str()) {
+                Some("csv") => "csv",
+                Some("data") => "timewarrior",
+                Some("ics" | "ical") => "ics",
+                _ => {
+                    let message = format!("unknown format of {}, use --from", file.display());
+                    return Err(Error::Argument(message));
+                }
+            },
+        };
+        let content = fs::read_to_string(file).map_err(|err| Error::io(file, err))?;
+        let parsed = match format {
+            "timewarrior" => formats::timewarrior::parse(&content, &chrono::Local),
+            "ics" => formats::ics::parse(&content, &chrono::Local),
+            _ => formats::csv::parse(&content),
+        };
+        entries.extend(parsed.map_err(|err| Error::from(err).in_file(file))?);
+    }
+
+    let directory = match matches.get_one::<PathBuf>("dir") {
+        Some(directory) => directory.clone(),
+        None => config.directory(),
+    };
+    let dry_run = matches.get_flag("dry-run");
+    let mut by_week: Vec<(chrono::IsoWeek, Vec<timelog::Entry>)> = vec![];
+    entries.sort();
+    for entry in entries {
+        let week = chrono::Datelike::iso_week(&entry.date());
+        match by_week.last_mut() {
+            Some((last, entries)) if *last == week => entries.push(entry),
+            _ => by_week.push((week, vec![entry])),
+        }
+    }
+    for (week, entries) in by_week {
+        let name = format!("{}-w{:02}", week.year(), week.week());
+        let path = directory.join(format!("{}.md", name));
+        let content = if path.is_file() {
+            fs::read_to_string(&path).map_err(|err| Error::io(&path, err))?
+        } else {
+            format!("# {}\n", name.to_uppercase())
+        };
+        let context = timelog::Context {
+            headings: config.headings.clone(),
+            ..timelog::Context::from_path(&path)
+        };
+        let (content, inserted) =
+            timelog::insert(&content, &entries, &context).map_err(|err| err.in_file(&path))?;
+        let skipped = entries.len() - inserted.len();
+        super::print(&format!(
+            "{}: {} added, {} already logged\n",
+            path.display(),
+            inserted.len(),
+            skipped
+        ))?;
+        if !dry_run && !inserted.is_empty() {
+            fs::create_dir_all(&directory).map_err(|err| Error::io(&directory, err))?;
+            fs::write(&path, content).map_err(|err| Error::io(&path, err))?;
+        }
+    }
+    Ok(())
+}
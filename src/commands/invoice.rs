@@ -0,0 +1,46 @@
+use crate::{config::Config, report::invoice::Invoice, utils::DateRange, Error};
+use clap::{arg, Command};
+use std::{fs, path::PathBuf};
+
+pub fn define() -> Command {
+    Command::new("invoice")
+        .about("Itemise the hours spent for a client, with amounts from their hourly rates")
+        .arg(
+            arg!(<range> "Dates to invoice, like 2024-02 or 2024-w07")
+                .value_parser(clap::value_parser!(DateRange)),
+        )
+        .arg(arg!(--client <client> "Client from the config to invoice").required(true))
+        .arg(
+            arg!(-f --format <format> "Output format")
+                .value_parser(["markdown", "html", "json"])
+                .default_value("markdown"),
+        )
+        .arg(
+            arg!(-o --output <file> "File to write to [default: stdout]")
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+        .arg(super::depth_arg())
+}
+
+pub fn run(matches: &clap::ArgMatches, config: &Config) -> Result<(), Error> {
+    let range = matches.get_one::<DateRange>("range").unwrap();
+    let name = matches.get_one::<String>("client").unwrap();
+    let client = config
+        .clients
+        .get(name)
+        .ok_or_else(|| Error::Argument(format!("no client {} in the config", name)))?;
+    // Breaks aren't billed, though unlogged breaks aren't deducted either.
+    let (log, _) = config
+        .breaks
+        .split(&super::read_logs(range, matches, config)?);
+    let invoice = Invoice::new(name, client, &log, range, config.rounding.as_ref())?;
+    let output = match matches.get_one::<String>("format").unwrap().as_str() {
+        "html" => invoice.html(),
+        "json" => format!("{}\n", invoice.json()),
+        _ => invoice.markdown(),
+    };
+    match matches.get_one::<PathBuf>("output") {
+        Some(path) => fs::write(path, output).map_err(|err| Error::io(path, err)),
+        None => super::print(&output),
+    }
+}
@@ -0,0 +1,71 @@
+use crate::Error;
+use clap::{arg, Command};
+use clap_mangen::{roff, Man};
+use std::{fs, path::PathBuf};
+
+pub fn define() -> Command {
+    Command::new("man")
+        .about("Generate man pages, for myw and each command")
+        .arg(
+            arg!(-o --output <directory> "Directory to write the pages to [default: print myw(1) to stdout]")
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+}
+
+pub fn run(matches: &clap::ArgMatches) -> Result<(), Error> {
+    let mut command = super::cli();
+    command.build();
+    let Some(directory) = matches.get_one::<PathBuf>("output") else {
+        return super::print(&String::from_utf8_lossy(&page(&command)?));
+    };
+    let io = |err| Error::io(directory, err);
+    fs::create_dir_all(directory).map_err(io)?;
+    let man = Man::new(command.clone());
+    fs::write(directory.join(man.get_filename()), page(&command)?).map_err(io)?;
+    for subcommand in command.get_subcommands() {
+        Man::new(subcommand.clone())
+            .generate_to(directory)
+            .map_err(io)?;
+    }
+    Ok(())
+}
+
+/// The page for myw itself, with the syntax of logs after the commands.
+fn page(command: &Command) -> Result<Vec<u8>, std::io::Error> {
+    let man = Man::new(command.clone());
+    let mut page = vec![];
+    man.render_title(&mut page)?;
+    man.render_name_section(&mut page)?;
+    man.render_synopsis_section(&mut page)?;
+    man.render_description_section(&mut page)?;
+    man.render_options_section(&mut page)?;
+    man.render_subcommands_section(&mut page)?;
+    syntax().to_writer(&mut page)?;
+    man.render_version_section(&mut page)?;
+    Ok(page)
+}
+
+/// The syntax of logs as man page sections, with examples as unfilled blocks.
+fn syntax() -> roff::Roff {
+    let mut roff = roff::Roff::new();
+    for (title, body) in super::help::SYNTAX {
+        roff.control("SH", [title]);
+        for paragraph in body.split("\n\n") {
+            match paragraph.starts_with("    ") {
+                true => {
+                    roff.control("PP", [])
+                        .control("nf", [])
+                        .control("RS", ["4"]);
+                    for line in paragraph.lines() {
+                        roff.text([roff::roman(line.trim_start())]);
+                    }
+                    roff.control("RE", []).control("fi", []);
+                }
+                false => {
+                    roff.control("PP", []).text([roff::roman(paragraph)]);
+                }
+            }
+        }
+    }
+    roff
+}
@@ -1,21 +1,201 @@
-pub mod debug;
-pub mod report;
+#[cfg(feature = "cache")]
+mod cache;
+mod completions;
+mod debug;
+mod export;
+mod help;
+mod import;
+mod invoice;
+mod man;
+mod projects;
+mod report;
+mod stats;
+mod suggest;
 
-use std::{error::Error, fmt, path::PathBuf};
+use crate::{config::Config, timelog, utils::DateRange, Error};
+use clap::{arg, Arg};
+use colored::Colorize;
+use std::{fs, io, io::Write, path::Path, path::PathBuf, process::ExitCode};
 
-#[derive(Debug, Clone)]
-struct NoSuchFileError {}
-impl Error for NoSuchFileError {}
-impl fmt::Display for NoSuchFileError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "no such file")
+/// The command line interface, with all subcommands.
+fn cli() -> clap::Command {
+    let command = clap::command!()
+        .disable_help_subcommand(true)
+        .after_long_help("See `myw help syntax` for how to write logs, and `myw help <command>` for each command.")
+        .arg(
+            arg!(-c --config <file> "Path to the config file [default: ~/.config/myw/config.toml]")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true),
+        )
+        .subcommand(report::define())
+        .subcommand(import::define())
+        .subcommand(export::define())
+        .subcommand(invoice::define())
+        .subcommand(stats::define())
+        .subcommand(projects::define())
+        .subcommand(suggest::define())
+        .subcommand(completions::define())
+        .subcommand(man::define())
+        .subcommand(help::define())
+        .subcommand(debug::define());
+    #[cfg(feature = "cache")]
+    let command = command.subcommand(cache::define());
+    command
+}
+
+/// Runs the `myw` command line tool with the arguments of the process, printing errors to stderr.
+pub fn main() -> ExitCode {
+    match run(&cli().get_matches()) {
+        Ok(()) => ExitCode::SUCCESS,
+        // The output was piped into something that stopped reading, like `head`.
+        Err(Error::Io { path: None, source }) if source.kind() == io::ErrorKind::BrokenPipe => {
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}: {}", "error".red().bold(), err);
+            ExitCode::from(err.exit_code())
+        }
     }
 }
 
-fn parse_file_path(input: &str) -> Result<PathBuf, NoSuchFileError> {
+fn run(matches: &clap::ArgMatches) -> Result<(), Error> {
+    let config = match matches.get_one::<PathBuf>("config") {
+        Some(path) => Config::load(path)?,
+        None => Config::load_default()?,
+    };
+
+    match matches.subcommand() {
+        Some(("report", matches)) => report::run(matches, &config)?,
+        Some(("import", matches)) => import::run(matches, &config)?,
+        Some(("export", matches)) => export::run(matches, &config)?,
+        Some(("invoice", matches)) => invoice::run(matches, &config)?,
+        Some(("stats", matches)) => stats::run(matches, &config)?,
+        Some(("projects", matches)) => projects::run(matches, &config)?,
+        Some(("suggest", matches)) => suggest::run(matches, &config)?,
+        Some(("completions", matches)) => completions::run(matches)?,
+        Some(("man", matches)) => man::run(matches)?,
+        Some(("help", matches)) => help::run(matches)?,
+        Some(("debug", matches)) => debug::run(matches, &config)?,
+        #[cfg(feature = "cache")]
+        Some(("cache", matches)) => cache::run(matches)?,
+        None => {}
+        Some((name, _)) => return Err(Error::Argument(format!("unknown command {}", name))),
+    }
+
+    Ok(())
+}
+
+fn parse_file_path(input: &str) -> Result<PathBuf, Error> {
     let path = PathBuf::from(input);
     match path.is_file() {
         true => Ok(path),
-        false => Err(NoSuchFileError {}),
+        false => Err(Error::Argument("no such file".to_owned())),
+    }
+}
+
+/// Writes to stdout, failing instead of panicking when it's closed, like when piped into `head`.
+fn print(output: &str) -> Result<(), Error> {
+    std::io::stdout().lock().write_all(output.as_bytes())?;
+    Ok(())
+}
+
+fn range_arg() -> Arg {
+    arg!(-r --range <range> "Only include dates in a range, like 2024-02-13, 2024-w07 or 2024-02")
+        .value_parser(clap::value_parser!(DateRange))
+}
+
+fn depth_arg() -> Arg {
+    arg!(--depth <depth> "How deeply nested in block quotes or sub-lists entries may be")
+        .value_parser(clap::value_parser!(usize))
+        .default_value("0")
+}
+
+/// Parses the log in the given file, printing any warnings to stderr.
+fn read_log(
+    file: &Path,
+    matches: &clap::ArgMatches,
+    config: &Config,
+) -> Result<timelog::Log, Error> {
+    read_log_files(&[file.to_owned()], matches, config)
+}
+
+fn print_warnings(file: &Path, warnings: &[timelog::Warning]) {
+    for warning in warnings {
+        eprintln!(
+            "{}: {}: {}",
+            "warning".yellow().bold(),
+            file.display(),
+            warning
+        );
+    }
+}
+
+/// Parses a log, or takes it from the cache if it hasn't changed since it was last parsed.
+fn parse_log(
+    file: &Path,
+    context: &timelog::Context,
+) -> Result<(timelog::Log, Vec<timelog::Warning>), Error> {
+    #[cfg(feature = "cache")]
+    if let Some(directory) = timelog::Cache::default_directory() {
+        return timelog::Cache::new(directory).parse(file, context);
+    }
+    let content = fs::read_to_string(file).map_err(|err| Error::io(file, err))?;
+    timelog::Log::parse_with_warnings(&content, context).map_err(|err| err.in_file(file))
+}
+
+/// Parses the logs in the configured directory with a file name in the given range, like
+/// `2024-w07.md` for `2024-02`, and keeps the entries within the range.
+fn read_logs(
+    range: &DateRange,
+    matches: &clap::ArgMatches,
+    config: &Config,
+) -> Result<timelog::Log, Error> {
+    let paths = log_files(&config.directory(), range)?
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect::<Vec<_>>();
+    Ok(read_log_files(&paths, matches, config)?.within(range))
+}
+
+/// The logs in a directory with a file name in the given range, in order, with their ranges.
+fn log_files(directory: &Path, range: &DateRange) -> Result<Vec<(PathBuf, DateRange)>, Error> {
+    let mut files = fs::read_dir(directory)
+        .map_err(|err| Error::io(directory, err))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|path| {
+            path.file_name()
+                .and_then(|name| DateRange::from_file_name(&name.to_string_lossy()))
+                .filter(|file_range| file_range.overlaps(range))
+                .map(|file_range| (path, file_range))
+        })
+        .collect::<Vec<_>>();
+    files.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(files)
+}
+
+/// Parses the logs in the given files at once, on as many threads as configured. Warnings are
+/// printed, and the first error is returned, in the order of the files.
+fn read_log_files(
+    files: &[PathBuf],
+    matches: &clap::ArgMatches,
+    config: &Config,
+) -> Result<timelog::Log, Error> {
+    let depth = *matches.get_one::<usize>("depth").unwrap();
+    let results = crate::utils::map_parallel(files, config.threads(), |file| {
+        let context = timelog::Context {
+            depth,
+            headings: config.headings.clone(),
+            reader: config.reader,
+            ..timelog::Context::from_path(file)
+        };
+        parse_log(file, &context)
+    });
+    let mut entries = vec![];
+    for (file, result) in files.iter().zip(results) {
+        let (log, warnings) = result?;
+        print_warnings(file, &warnings);
+        entries.extend(log);
     }
+    Ok(timelog::Log::from(entries))
 }
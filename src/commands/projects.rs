@@ -0,0 +1,42 @@
+use crate::{config::Config, timelog::Time, utils::DateRange, Error};
+use clap::{arg, Command};
+
+pub fn define() -> Command {
+    Command::new("projects")
+        .about("List the projects in the logs in the configured directory, most used first")
+        .arg(arg!([prefix] "Only list projects starting with a prefix, ignoring case"))
+        .arg(super::range_arg())
+        .arg(super::depth_arg())
+}
+
+pub fn run(matches: &clap::ArgMatches, config: &Config) -> Result<(), Error> {
+    let range = matches
+        .get_one::<DateRange>("range")
+        .cloned()
+        .unwrap_or(DateRange(chrono::NaiveDate::MIN, chrono::NaiveDate::MAX));
+    let prefix = matches
+        .get_one::<String>("prefix")
+        .map(|prefix| prefix.to_lowercase())
+        .unwrap_or_default();
+    let log = super::read_logs(&range, matches, config)?;
+    let mut projects = log
+        .by_project()
+        .into_iter()
+        .filter(|(project, _)| project.to_lowercase().starts_with(&prefix))
+        .map(|(project, log)| {
+            // Leave isn't a project.
+            let count = log
+                .iter()
+                .filter(|entry| *entry.time() != Time::AllDay)
+                .count();
+            (project, count)
+        })
+        .filter(|(_, count)| *count > 0)
+        .collect::<Vec<_>>();
+    projects.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+    let output = projects
+        .iter()
+        .map(|(project, _)| format!("{}\n", project))
+        .collect::<String>();
+    super::print(&output)
+}
@@ -1,7 +1,13 @@
-use crate::report::{Fill, Report};
-use crate::timelog;
+use crate::config::Config;
+use crate::report::{Html, Markdown, Render, Report, Text, Timeline};
+use crate::{utils::DateRange, Error};
 use clap::{arg, Command};
-use std::{error::Error, fs, path::PathBuf};
+use colored::Colorize;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::{io, path::Path, path::PathBuf, sync::mpsc, time::Duration};
+
+/// Clears the terminal and moves the cursor to the top left.
+static CLEAR: &str = "\x1b[2J\x1b[H";
 
 pub fn define() -> Command {
     Command::new("report")
@@ -12,16 +18,136 @@ pub fn define() -> Command {
                 .required(true)
                 .value_parser(super::parse_file_path),
         )
+        .arg(
+            arg!(-f --format <format> "Output format")
+                .value_parser(["text", "markdown", "html"])
+                .default_value("text"),
+        )
+        .arg(
+            arg!(--timeline "Show each day as a time axis with a block per project")
+                .conflicts_with("format"),
+        )
+        .arg(arg!(-w --watch "Render the report again whenever a log changes"))
+        .arg(super::depth_arg())
 }
 
-pub fn run(matches: &clap::ArgMatches) -> Result<(), Box<dyn Error>> {
+pub fn run(matches: &clap::ArgMatches, config: &Config) -> Result<(), Error> {
+    if matches.get_flag("watch") {
+        return watch(matches, config);
+    }
+    super::print(&format!("{}\n", render(matches, config)?))
+}
+
+/// Renders the report, then renders it again after every change to the file or to a log in the
+/// configured directory. When that fails, the previous report stays on screen with the error.
+fn watch(matches: &clap::ArgMatches, config: &Config) -> Result<(), Error> {
     let file = matches.get_one::<PathBuf>("file").unwrap();
-    let content = fs::read_to_string(file)?;
-    let log = timelog::Log::parse(&content);
-    let reports = Report::by_date_by_project(&log, Fill::Padded);
-    for report in reports {
-        println!("{}", report.text());
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).map_err(io::Error::other)?;
+    // Editors often save by replacing the file, so the directories are watched rather than files.
+    let parent = file
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    watcher
+        .watch(parent, RecursiveMode::NonRecursive)
+        .map_err(|err| Error::io(parent, io::Error::other(err)))?;
+    let directory = config.directory();
+    if directory.is_dir() {
+        watcher
+            .watch(&directory, RecursiveMode::NonRecursive)
+            .map_err(|err| Error::io(&directory, io::Error::other(err)))?;
     }
-    println!("{}", Report::total(&log).text());
-    Ok(())
+    let is_log = |path: &PathBuf| {
+        path.extension().is_some_and(|ext| ext == "md") || path.file_name() == file.file_name()
+    };
+
+    let mut previous = String::new();
+    loop {
+        super::print(CLEAR)?;
+        match render(matches, config) {
+            Ok(output) => {
+                super::print(&format!("{}\n", output))?;
+                previous = output;
+            }
+            Err(err) => super::print(&format!(
+                "{}\n{}: {}\n",
+                previous,
+                "error".red().bold(),
+                err
+            ))?,
+        }
+        // Wait for a change to a log, then for the other events of the same save.
+        loop {
+            let Ok(event) = receiver.recv() else {
+                return Ok(());
+            };
+            let event = event.map_err(io::Error::other)?;
+            if !matches!(event.kind, EventKind::Access(_)) && event.paths.iter().any(is_log) {
+                break;
+            }
+        }
+        while receiver.recv_timeout(Duration::from_millis(100)).is_ok() {}
+    }
+}
+
+fn render(matches: &clap::ArgMatches, config: &Config) -> Result<String, Error> {
+    let file = matches.get_one::<PathBuf>("file").unwrap();
+    let entries = super::read_log(file, matches, config)?;
+    let timeline = matches.get_flag("timeline");
+    // The timeline takes the place of the reports per day.
+    let mut builder = Report::builder(&entries).config(config).days(!timeline);
+    // Days without entries count against targets, up to today.
+    let today = chrono::Local::now().date_naive();
+    if let Some(DateRange(from, until)) = file
+        .file_name()
+        .and_then(|name| DateRange::from_file_name(&name.to_string_lossy()))
+        .filter(|range| range.0 <= today)
+    {
+        builder = builder.range(DateRange(from, until.min(today)));
+    }
+    let directory = config.directory();
+    let budgets = match budgets_range(config) {
+        Some(range) if directory.is_dir() => Some(super::read_logs(&range, matches, config)?),
+        Some(_) => {
+            eprintln!(
+                "{}: {}: no such directory, leaving out budgets",
+                "warning".yellow().bold(),
+                directory.display()
+            );
+            None
+        }
+        None => None,
+    };
+    if let Some(budgets) = &budgets {
+        builder = builder.budgets(budgets);
+    }
+    let reports = builder.build();
+    let output = match matches.get_one::<String>("format").unwrap().as_str() {
+        "markdown" => Markdown.document(&reports),
+        "html" => Html {
+            title: file
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into(),
+        }
+        .document(&reports),
+        _ if timeline => {
+            let width = terminal_size::terminal_size()
+                .map(|(terminal_size::Width(width), _)| width.into())
+                .unwrap_or(80);
+            let timeline = Timeline { width }.render(&entries);
+            format!("{}\n{}", timeline, Text.document(&reports))
+        }
+        _ => Text.document(&reports),
+    };
+    Ok(output)
+}
+
+/// The range covering all budgets, to read the logs they are spent in.
+fn budgets_range(config: &Config) -> Option<DateRange> {
+    let from = config.budgets.values().map(|budget| budget.range.0).min()?;
+    let until = config.budgets.values().map(|budget| budget.range.1).max()?;
+    Some(DateRange(from, until))
 }
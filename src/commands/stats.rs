@@ -0,0 +1,37 @@
+use crate::{config::Config, report::stats::Stats, utils::DateRange, Error};
+use clap::{arg, Command};
+
+pub fn define() -> Command {
+    Command::new("stats")
+        .about("Show averages, trends and distributions of the time spent")
+        .arg(
+            arg!([range] "Dates to include, like 2024 or 2024-02 [default: the current year]")
+                .value_parser(clap::value_parser!(DateRange)),
+        )
+        .arg(
+            arg!(-f --format <format> "Output format")
+                .value_parser(["text", "json"])
+                .default_value("text"),
+        )
+        .arg(super::depth_arg())
+}
+
+pub fn run(matches: &clap::ArgMatches, config: &Config) -> Result<(), Error> {
+    use chrono::Datelike;
+    let today = chrono::Local::now().date_naive();
+    let DateRange(from, until) = match matches.get_one::<DateRange>("range") {
+        Some(range) => range.clone(),
+        None => DateRange::year(today.year()).unwrap_or(DateRange::day(today)),
+    };
+    // Days still to come would only lower the averages.
+    let range = DateRange(from, until.min(today).max(from));
+    let log = super::read_logs(&range, matches, config)?;
+    let mut calendar = config.calendar.clone();
+    calendar.add_leave(&log);
+    let (log, _) = config.breaks.split(&log);
+    let stats = Stats::new(&log, &range, &calendar);
+    match matches.get_one::<String>("format").unwrap().as_str() {
+        "json" => super::print(&format!("{}\n", stats.json())),
+        _ => super::print(&stats.text()),
+    }
+}
@@ -0,0 +1,170 @@
+use crate::formats::git;
+use crate::{config::Config, timelog, utils::DateRange, Error};
+use clap::{arg, Command};
+use colored::Colorize;
+use std::{
+    fs, io,
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+pub fn define() -> Command {
+    Command::new("suggest")
+        .about("Suggest entries for a day from the commits in the configured git repositories")
+        .arg(
+            arg!([date] "Date to suggest entries for [default: today]").value_parser(
+                |input: &str| {
+                    input
+                        .parse::<chrono::NaiveDate>()
+                        .map_err(|_| "expected a date like 2024-02-13")
+                },
+            ),
+        )
+        .arg(arg!(-i --insert "Add the entries that don't overlap logged ones or each other to the log"))
+        .arg(arg!(-y --yes "Insert without asking for confirmation").requires("insert"))
+        .arg(super::depth_arg())
+}
+
+pub fn run(matches: &clap::ArgMatches, config: &Config) -> Result<(), Error> {
+    let date = matches
+        .get_one::<chrono::NaiveDate>("date")
+        .copied()
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+    let repositories = config.repositories();
+    if repositories.is_empty() {
+        return Err(Error::Argument(
+            "no repositories configured in suggest.repositories".to_owned(),
+        ));
+    }
+    let mut entries = vec![];
+    for (repository, project) in repositories {
+        let authors = match config.suggest.authors.is_empty() {
+            true => vec![git(&repository, &["config", "user.email"])?
+                .trim()
+                .to_owned()],
+            false => config.suggest.authors.clone(),
+        };
+        // Commits are committed when they're authored or later, as when rebasing.
+        let since = format!("--since={} 00:00", date);
+        let format = format!("--format={}", git::LOG_FORMAT);
+        let output = git(
+            &repository,
+            &["log", "--all", "--no-merges", &since, &format],
+        )?;
+        let commits = git::parse(&output, &chrono::Local)
+            .map_err(|err| Error::from(err).in_file(&repository))?;
+        entries.extend(git::suggest(
+            &commits,
+            date,
+            project,
+            &authors,
+            &config.suggest,
+        ));
+    }
+    entries.sort();
+    if entries.is_empty() {
+        eprintln!("{}: no commits on {}", "warning".yellow().bold(), date);
+        return Ok(());
+    }
+    if !matches.get_flag("insert") {
+        return super::print(&list(&entries));
+    }
+
+    let logged = match config.directory().is_dir() {
+        true => super::read_logs(&DateRange::day(date), matches, config)?,
+        false => timelog::Log::new(),
+    };
+    let (entries, overlapping) = git::accept(entries, &logged);
+    let path = log_file(config, date)?;
+    if entries.is_empty() {
+        return super::print(&format!(
+            "{}: nothing to add, {} overlap logged or suggested entries\n",
+            path.display(),
+            overlapping.len()
+        ));
+    }
+    super::print(&list(&entries))?;
+    if !matches.get_flag("yes") && !confirm(&format!("Insert into {}?", path.display()))? {
+        return Ok(());
+    }
+    let content = if path.is_file() {
+        fs::read_to_string(&path).map_err(|err| Error::io(&path, err))?
+    } else {
+        let week = chrono::Datelike::iso_week(&date);
+        format!("# {}-W{:02}\n", week.year(), week.week())
+    };
+    let context = timelog::Context {
+        depth: *matches.get_one::<usize>("depth").unwrap(),
+        headings: config.headings.clone(),
+        ..timelog::Context::from_path(&path)
+    };
+    let (content, inserted) =
+        timelog::insert(&content, &entries, &context).map_err(|err| err.in_file(&path))?;
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory).map_err(|err| Error::io(directory, err))?;
+    }
+    fs::write(&path, content).map_err(|err| Error::io(&path, err))?;
+    super::print(&format!(
+        "{}: {} added, {} overlap logged or suggested entries\n",
+        path.display(),
+        inserted.len(),
+        overlapping.len()
+    ))
+}
+
+/// Runs git in a repository, returning its output.
+fn git(repository: &Path, args: &[&str]) -> Result<String, Error> {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(repository)
+        .args(args)
+        .output()
+        .map_err(|err| {
+            Error::io(
+                repository,
+                io::Error::other(format!("cannot run git: {}", err)),
+            )
+        })?;
+    if !output.status.success() {
+        let message = match String::from_utf8_lossy(&output.stderr).trim() {
+            "" => format!("git {} failed", args.join(" ")),
+            message => message.to_owned(),
+        };
+        return Err(Error::io(repository, io::Error::other(message)));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn list(entries: &[timelog::Entry]) -> String {
+    entries
+        .iter()
+        .map(|entry| format!("- {}\n", entry.to_markdown()))
+        .collect()
+}
+
+/// The log in the configured directory for a date, preferring the one that covers the fewest
+/// days, like a week file over a month file. Without one, it's a new week file.
+fn log_file(config: &Config, date: chrono::NaiveDate) -> Result<PathBuf, Error> {
+    let directory = config.directory();
+    let files = match directory.is_dir() {
+        true => super::log_files(&directory, &DateRange::day(date))?,
+        false => vec![],
+    };
+    let file = files.into_iter().min_by_key(|(_, range)| range.1 - range.0);
+    Ok(match file {
+        Some((path, _)) => path,
+        None => {
+            let week = chrono::Datelike::iso_week(&date);
+            directory.join(format!("{}-w{:02}.md", week.year(), week.week()))
+        }
+    })
+}
+
+/// Asks a yes or no question on the terminal, where anything but yes is no.
+fn confirm(question: &str) -> Result<bool, Error> {
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
@@ -0,0 +1,421 @@
+use crate::formats::git::Suggest;
+use crate::report::{invoice::Client, Breaks, Budget, Calendar, Rounding, Targets};
+use crate::timelog::{HeadingFormats, Reader};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    env, error, fmt, fs, io,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
+
+/// The most hours a target, budget or break can be set to.
+static MAX_HOURS: i64 = 1_000_000;
+
+/// The highest hourly rate, so amounts on invoices can't overflow.
+static MAX_RATE: i64 = 1_000_000_000;
+
+/// User configuration, read from `config.toml` in the `myw` configuration directory.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Where log files are kept. A leading `~` refers to the home directory.
+    pub directory: Option<PathBuf>,
+    pub headings: HeadingFormats,
+    /// How logs are read: `markdown`, or the faster `lines` or `plain-lines`.
+    pub reader: Reader,
+    /// How many logs are parsed at once, defaulting to one per CPU.
+    pub threads: Option<NonZeroUsize>,
+    /// How billed time is rounded in reports and invoices.
+    pub(crate) rounding: Option<Rounding>,
+    /// Billing details per client, for invoices.
+    pub(crate) clients: BTreeMap<String, Client>,
+    pub(crate) targets: Targets,
+    /// Hours budgeted per project.
+    pub(crate) budgets: BTreeMap<String, Budget>,
+    pub(crate) calendar: Calendar,
+    pub(crate) breaks: Breaks,
+    pub(crate) suggest: Suggest,
+}
+
+impl Config {
+    /// The path of the configuration file: `$MYW_CONFIG`, or `myw/config.toml` in
+    /// `$XDG_CONFIG_HOME` (defaulting to `~/.config`).
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os("MYW_CONFIG") {
+            return Some(PathBuf::from(path));
+        }
+        let dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(dir.join("myw").join("config.toml"))
+    }
+
+    /// The directory with log files, falling back to the current directory.
+    pub fn directory(&self) -> PathBuf {
+        match &self.directory {
+            Some(directory) => expand_home(directory),
+            None => PathBuf::from("."),
+        }
+    }
+
+    /// How many logs to parse at once.
+    pub fn threads(&self) -> usize {
+        self.threads
+            .map_or_else(crate::utils::default_threads, NonZeroUsize::get)
+    }
+
+    /// The repositories to suggest entries from, with their paths expanded, and their projects.
+    pub(crate) fn repositories(&self) -> Vec<(PathBuf, &str)> {
+        self.suggest
+            .repositories
+            .iter()
+            .map(|(path, project)| (expand_home(path), project.as_str()))
+            .collect()
+    }
+
+    /// Loads the configuration from the default path, if there is a file there.
+    pub fn load_default() -> Result<Self, ConfigError> {
+        match Self::default_path() {
+            Some(path) if path.is_file() => Self::load(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Loads the configuration from a file, along with the public holidays file it refers to.
+    /// A relative holidays path is relative to the configuration file.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let content =
+            fs::read_to_string(path).map_err(|err| ConfigError::Io(path.to_owned(), err))?;
+        let mut config = Self::parse(&content)?;
+        if let Some(file) = &config.calendar.holidays_file {
+            let file = path
+                .parent()
+                .unwrap_or(Path::new("."))
+                .join(expand_home(file));
+            let content =
+                fs::read_to_string(&file).map_err(|err| ConfigError::Io(file.clone(), err))?;
+            config.calendar.holidays =
+                Calendar::parse_holidays(&content).map_err(|(line, message)| {
+                    ConfigError::Invalid(format!("{}: line {}: {}", file.display(), line, message))
+                })?;
+        }
+        Ok(config)
+    }
+
+    pub fn parse(input: &str) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(input).map_err(ConfigError::Toml)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        for format in self.headings.formats.iter() {
+            let items = chrono::format::StrftimeItems::new(format);
+            if items
+                .into_iter()
+                .any(|item| item == chrono::format::Item::Error)
+            {
+                return Err(ConfigError::Invalid(format!(
+                    "invalid date format \"{}\" in headings.formats",
+                    format
+                )));
+            }
+        }
+        let clients = self.clients.iter().filter_map(|(name, client)| {
+            client
+                .rounding
+                .as_ref()
+                .map(|rounding| (format!("clients.{}.rounding", name), rounding))
+        });
+        for (key, rounding) in self
+            .rounding
+            .as_ref()
+            .map(|rounding| ("rounding".to_owned(), rounding))
+            .into_iter()
+            .chain(clients)
+        {
+            if rounding.increment == 0 {
+                return Err(ConfigError::Invalid(format!(
+                    "{}.increment must be at least one minute",
+                    key
+                )));
+            }
+        }
+        let hours =
+            [
+                ("targets.daily".to_owned(), self.targets.daily),
+                ("targets.weekly".to_owned(), self.targets.weekly),
+            ]
+            .into_iter()
+            .chain(self.budgets.iter().map(|(project, budget)| {
+                (format!("budgets.{}.hours", project), Some(budget.hours))
+            }))
+            .chain(
+                self.breaks
+                    .deductions
+                    .iter()
+                    .map(|deduction| ("breaks.deductions.after".to_owned(), Some(deduction.after))),
+            );
+        for (key, hours) in hours {
+            if hours.is_some_and(|hours| hours < Decimal::ZERO || hours > Decimal::from(MAX_HOURS))
+            {
+                return Err(ConfigError::Invalid(format!(
+                    "{} must be between 0 and {} hours",
+                    key, MAX_HOURS
+                )));
+            }
+        }
+        for (name, client) in self.clients.iter() {
+            let rates = client.rate.iter().chain(client.rates.values());
+            if rates
+                .into_iter()
+                .any(|rate| *rate < Decimal::ZERO || *rate > Decimal::from(MAX_RATE))
+            {
+                return Err(ConfigError::Invalid(format!(
+                    "rates of clients.{} must be between 0 and {}",
+                    name, MAX_RATE
+                )));
+            }
+        }
+        for (key, minutes) in [
+            ("suggest.gap", self.suggest.gap),
+            ("suggest.lead", self.suggest.lead),
+        ] {
+            if minutes > 24 * 60 {
+                return Err(ConfigError::Invalid(format!(
+                    "{} must be at most a day ({} minutes)",
+                    key,
+                    24 * 60
+                )));
+            }
+        }
+        if let Some(path) = self
+            .suggest
+            .repositories
+            .iter()
+            .find_map(|(path, project)| project.trim().is_empty().then_some(path))
+        {
+            return Err(ConfigError::Invalid(format!(
+                "no project for {} in suggest.repositories",
+                path.display()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Expands a leading `~` to the home directory.
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => path.to_owned(),
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, io::Error),
+    Toml(toml::de::Error),
+    Invalid(String),
+}
+
+impl error::Error for ConfigError {}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "cannot read config {}: {}", path.display(), err),
+            Self::Toml(err) => write!(f, "invalid config: {}", err),
+            Self::Invalid(message) => write!(f, "invalid config: {}", message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timelog::Locale;
+
+    #[test]
+    fn parse_empty() {
+        assert_eq!(Config::default(), Config::parse("").unwrap());
+    }
+
+    #[test]
+    fn parse_headings() {
+        let config = Config::parse(indoc::indoc! {r#"
+            [headings]
+            formats = ["%d-%m-%Y", "%Y/%m/%d"]
+            locales = ["en", "nl_BE"]
+        "#})
+        .unwrap();
+        assert_eq!(
+            HeadingFormats {
+                formats: vec!["%d-%m-%Y".into(), "%Y/%m/%d".into()],
+                locales: vec![Locale::En, Locale::Nl],
+            },
+            config.headings
+        );
+    }
+
+    #[test]
+    fn parse_reader() {
+        let config = Config::parse(r#"reader = "plain-lines""#).unwrap();
+        assert_eq!(Reader::PlainLines, config.reader);
+        assert_eq!(Reader::Markdown, Config::default().reader);
+        assert!(Config::parse(r#"reader = "lines-ish""#).is_err());
+    }
+
+    #[test]
+    fn parse_threads() {
+        assert_eq!(4, Config::parse("threads = 4").unwrap().threads());
+        assert!(Config::default().threads() >= 1);
+        assert!(Config::parse("threads = 0").is_err());
+    }
+
+    #[test]
+    fn parse_suggest() {
+        let config = Config::parse(indoc::indoc! {r#"
+            [suggest]
+            gap = 60
+            authors = ["me@example.com"]
+
+            [suggest.repositories]
+            "/src/abc" = "ABC"
+        "#})
+        .unwrap();
+        assert_eq!(60, config.suggest.gap);
+        assert_eq!(30, config.suggest.lead);
+        assert_eq!(
+            vec![(PathBuf::from("/src/abc"), "ABC")],
+            config.repositories()
+        );
+        assert!(Config::parse("suggest.lead = 1441").is_err());
+        assert!(Config::parse("suggest.repositories = { \"/src/abc\" = \" \" }").is_err());
+    }
+
+    #[test]
+    fn parse_directory() {
+        let config = Config::parse(r#"directory = "/home/me/logs""#).unwrap();
+        assert_eq!(PathBuf::from("/home/me/logs"), config.directory());
+        assert_eq!(PathBuf::from("."), Config::default().directory());
+    }
+
+    #[test]
+    fn parse_clients() {
+        let input = indoc::indoc! {r#"
+            [clients.acme]
+            currency = "EUR"
+            rate = 90
+            projects = ["ABC"]
+
+            [clients.acme.rates]
+            DEF = "100.50"
+        "#};
+        let config = Config::parse(input).unwrap();
+        let client = &config.clients["acme"];
+        assert_eq!(Some("EUR".to_owned()), client.currency);
+        assert_eq!(Some(90.into()), client.rate("ABC"));
+        assert_eq!(Some("100.50".parse().unwrap()), client.rate("DEF"));
+        assert!(client.includes("DEF"));
+        assert!(!client.includes("GHI"));
+    }
+
+    #[test]
+    fn parse_invalid_format() {
+        let result = Config::parse(indoc::indoc! {r#"
+            [headings]
+            formats = ["%d-%m-%Q"]
+        "#});
+        assert!(matches!(result, Err(ConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn parse_out_of_range() {
+        let result = Config::parse("[targets]\ndaily = -8\n");
+        assert!(matches!(result, Err(ConfigError::Invalid(_))));
+        let result = Config::parse("[clients.acme]\nrate = 1e20\n");
+        assert!(matches!(result, Err(ConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn parse_invalid_locale() {
+        let result = Config::parse(indoc::indoc! {r#"
+            [headings]
+            locales = ["xx"]
+        "#});
+        assert!(matches!(result, Err(ConfigError::Toml(_))));
+    }
+
+    #[test]
+    fn parse_unknown_key() {
+        let result = Config::parse("colour = true");
+        assert!(matches!(result, Err(ConfigError::Toml(_))));
+    }
+
+    #[test]
+    fn parse_rounding() {
+        let input = indoc::indoc! {r#"
+            [rounding]
+            increment = 15
+            mode = "up"
+
+            [clients.acme.rounding]
+            increment = 6
+            scope = "day"
+        "#};
+        let config = Config::parse(input).unwrap();
+        let expected = Rounding {
+            increment: 15,
+            mode: crate::report::rounding::Mode::Up,
+            scope: crate::report::rounding::Scope::Entry,
+        };
+        assert_eq!(Some(expected), config.rounding);
+        assert!(Config::parse("[rounding]\nincrement = 0").is_err());
+        assert!(Config::parse("[clients.acme.rounding]\nincrement = 0").is_err());
+    }
+
+    #[test]
+    fn parse_targets_and_budgets() {
+        let input = indoc::indoc! {r#"
+            [targets]
+            daily = 8
+            weekly = 37.5
+
+            [budgets.ABC]
+            hours = 100
+            range = "2024-02"
+        "#};
+        let config = Config::parse(input).unwrap();
+        assert_eq!(Some(chrono::TimeDelta::hours(8)), config.targets.daily());
+        assert_eq!(
+            Some(chrono::TimeDelta::minutes(2250)),
+            config.targets.weekly()
+        );
+        assert_eq!("2024-02".parse(), Ok(config.budgets["ABC"].range.clone()));
+        assert!(Config::parse("[budgets.ABC]\nhours = 1\nrange = \"February\"").is_err());
+    }
+
+    #[test]
+    fn parse_calendar() {
+        let config = Config::parse("[calendar]\nworkdays = [\"mon\", \"Tuesday\"]").unwrap();
+        let expected = vec![chrono::Weekday::Mon, chrono::Weekday::Tue];
+        assert_eq!(expected, config.calendar.workdays);
+        assert_eq!(5, Config::default().calendar.workdays.len());
+        assert!(Config::parse("[calendar]\nworkdays = [\"someday\"]").is_err());
+    }
+
+    #[test]
+    fn parse_breaks() {
+        let input = indoc::indoc! {r#"
+            [breaks]
+            projects = ["LUNCH"]
+            deductions = [{ after = 6, minutes = 30 }, { after = 9, minutes = 45 }]
+        "#};
+        let config = Config::parse(input).unwrap();
+        assert_eq!(vec!["LUNCH".to_owned()], config.breaks.projects);
+        assert_eq!(45, config.breaks.deductions[1].minutes);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::config::ConfigError;
+use crate::formats::FormatError;
+use crate::report::invoice::InvoiceError;
+use crate::utils::ParseDateRangeError;
+use std::{error, fmt, io, path::Path, path::PathBuf};
+
+/// Everything that can go wrong in `myw`, with the file it happened in where that's known.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Reading or writing a file failed, or writing to stdout when there's no path.
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+    /// A log isn't valid markdown.
+    Markdown {
+        path: Option<PathBuf>,
+        message: String,
+    },
+    Config(ConfigError),
+    DateRange(ParseDateRangeError),
+    /// An export of another time tracker can't be imported.
+    Format {
+        path: Option<PathBuf>,
+        source: FormatError,
+    },
+    Invoice(InvoiceError),
+    /// An entry made with [`Entry::new`](crate::timelog::Entry::new) or its builder is invalid.
+    Entry(String),
+    /// A command line argument that doesn't refer to anything, like a client that isn't in the
+    /// configuration.
+    Argument(String),
+}
+
+impl Error {
+    pub fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        Self::Io {
+            path: Some(path.into()),
+            source,
+        }
+    }
+
+    /// Adds the file the error happened in, unless it's already known.
+    pub fn in_file(self, file: &Path) -> Self {
+        match self {
+            Self::Io { path: None, source } => Self::io(file, source),
+            Self::Markdown {
+                path: None,
+                message,
+            } => Self::Markdown {
+                path: Some(file.to_owned()),
+                message,
+            },
+            Self::Format { path: None, source } => Self::Format {
+                path: Some(file.to_owned()),
+                source,
+            },
+            err => err,
+        }
+    }
+
+    /// An exit code per kind of error, from the conventional `sysexits.h`.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Argument(_) | Self::DateRange(_) => 64,
+            Self::Markdown { .. } | Self::Format { .. } | Self::Invoice(_) | Self::Entry(_) => 65,
+            Self::Io { source, .. } if source.kind() == io::ErrorKind::NotFound => 66,
+            Self::Io { .. } => 74,
+            Self::Config(_) => 78,
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Config(err) => Some(err),
+            Self::DateRange(err) => Some(err),
+            Self::Format { source, .. } => Some(source),
+            Self::Invoice(err) => Some(err),
+            Self::Markdown { .. } | Self::Entry(_) | Self::Argument(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let path = |path: &Option<PathBuf>| match path {
+            Some(path) => format!("{}: ", path.display()),
+            None => String::new(),
+        };
+        match self {
+            Self::Io { path: p, source } => write!(f, "{}{}", path(p), source),
+            Self::Markdown { path: p, message } => {
+                write!(f, "{}invalid markdown: {}", path(p), message)
+            }
+            Self::Config(err) => write!(f, "{}", err),
+            Self::DateRange(err) => write!(f, "{}", err),
+            Self::Format { path: p, source } => write!(f, "{}{}", path(p), source),
+            Self::Invoice(err) => write!(f, "{}", err),
+            Self::Entry(message) => write!(f, "invalid entry: {}", message),
+            Self::Argument(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Self {
+        Self::Io { path: None, source }
+    }
+}
+
+impl From<ConfigError> for Error {
+    fn from(err: ConfigError) -> Self {
+        Self::Config(err)
+    }
+}
+
+impl From<ParseDateRangeError> for Error {
+    fn from(err: ParseDateRangeError) -> Self {
+        Self::DateRange(err)
+    }
+}
+
+impl From<FormatError> for Error {
+    fn from(source: FormatError) -> Self {
+        Self::Format { path: None, source }
+    }
+}
+
+impl From<InvoiceError> for Error {
+    fn from(err: InvoiceError) -> Self {
+        Self::Invoice(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_file() {
+        let err = Error::from(FormatError {
+            line: 3,
+            message: "missing end".to_owned(),
+        })
+        .in_file(Path::new("export.csv"));
+        assert_eq!("export.csv: line 3: missing end", err.to_string());
+        assert_eq!(65, err.exit_code());
+    }
+
+    #[test]
+    fn exit_codes() {
+        let not_found = io::Error::from(io::ErrorKind::NotFound);
+        assert_eq!(66, Error::io("2024-w07.md", not_found).exit_code());
+        assert_eq!(74, Error::from(io::Error::other("disk full")).exit_code());
+        assert_eq!(64, Error::Argument("no client acme".to_owned()).exit_code());
+    }
+}
@@ -0,0 +1,140 @@
+use super::FormatError;
+use crate::timelog::Entry;
+
+static DATE_FORMATS: [&str; 4] = ["%Y-%m-%d", "%m/%d/%Y", "%d.%m.%Y", "%d-%m-%Y"];
+static TIME_FORMATS: [&str; 4] = ["%H:%M:%S", "%H:%M", "%I:%M:%S %p", "%I:%M %p"];
+
+/// Parses a detailed CSV export from Toggl Track or Clockify. Both have columns for the project,
+/// description, and start and end date and time (in local time). Other columns are ignored.
+pub fn parse(input: &str) -> Result<Vec<Entry>, FormatError> {
+    let input = input.trim_start_matches('\u{feff}');
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(input.as_bytes());
+    let headers = reader
+        .headers()
+        .map_err(|err| FormatError::new(1, err.to_string()))?
+        .clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(name))
+            .ok_or_else(|| FormatError::new(1, format!("missing column \"{}\"", name)))
+    };
+    let project = column("project")?;
+    let description = column("description")?;
+    let start_date = column("start date")?;
+    let start_time = column("start time")?;
+    let end_date = column("end date")?;
+    let end_time = column("end time")?;
+
+    let mut entries = vec![];
+    for record in reader.records() {
+        let record = record.map_err(|err| {
+            let line = err.position().map_or(0, |p| p.line() as usize);
+            FormatError::new(line, err.to_string())
+        })?;
+        let line = record.position().map_or(0, |p| p.line() as usize);
+        let field = |index: usize| record.get(index).unwrap_or_default();
+        let from = datetime(field(start_date), field(start_time))
+            .ok_or_else(|| FormatError::new(line, "invalid start date or time"))?;
+        let until = datetime(field(end_date), field(end_time))
+            .ok_or_else(|| FormatError::new(line, "invalid end date or time"))?;
+        if until <= from {
+            return Err(FormatError::new(line, "end is not after start"));
+        }
+        let Some(time) = super::interval(from, until) else {
+            continue;
+        };
+        entries.push(Entry {
+            date: from.date(),
+            time,
+            project: super::project(field(project)),
+            notes: super::notes(field(description)),
+        });
+    }
+    Ok(entries)
+}
+
+fn datetime(date: &str, time: &str) -> Option<chrono::NaiveDateTime> {
+    let date = DATE_FORMATS
+        .iter()
+        .find_map(|format| chrono::NaiveDate::parse_from_str(date.trim(), format).ok())?;
+    let time = TIME_FORMATS
+        .iter()
+        .find_map(|format| chrono::NaiveTime::parse_from_str(time.trim(), format).ok())?;
+    Some(date.and_time(time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn toggl() {
+        let result = parse(indoc::indoc! {r#"
+            User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags,Amount ()
+            Jane,jane@example.com,ACME,ABC,,"Standup, planning",Yes,2024-02-13,09:00:00,2024-02-13,10:30:00,01:30:00,,
+            Jane,jane@example.com,,,,,No,2024-02-13,23:00:00,2024-02-14,00:30:00,01:30:00,,
+        "#});
+        let expected = vec![
+            Entry::parse("9:00 - 10:30 ABC Standup, planning", &date(2024, 2, 13)).unwrap(),
+            Entry::parse("23:00 +1h30 \"No project\"", &date(2024, 2, 13)).unwrap(),
+        ];
+        assert_eq!(Ok(expected.clone()), result);
+        assert_eq!(Some("Standup, planning".into()), result.unwrap()[0].notes);
+    }
+
+    #[test]
+    fn clockify() {
+        let result = parse(indoc::indoc! {r#"
+            "Project","Client","Description","Task","User","Group","Email","Tags","Billable","Start Date","Start Time","End Date","End Time","Duration (h)","Duration (decimal)"
+            "Some project","ACME","","","Jane","","jane@example.com","","Yes","02/13/2024","09:00 AM","02/13/2024","01:15 PM","04:15:00","4.25"
+        "#});
+        let expected =
+            vec![Entry::parse("9:00 - 13:15 \"Some project\"", &date(2024, 2, 13)).unwrap()];
+        assert_eq!(Ok(expected), result);
+    }
+
+    #[test]
+    fn import_twice() {
+        let input = indoc::indoc! {"
+            Project,Description,Start date,Start time,End date,End time
+            ABC,work,2024-02-13,09:00:37,2024-02-13,10:30:12
+            ABC,blip,2024-02-13,11:00:05,2024-02-13,11:00:40
+        "};
+        let entries = parse(input).unwrap();
+        assert_eq!(
+            vec![Entry::parse("9:00 - 10:30 ABC work", &date(2024, 2, 13)).unwrap()],
+            entries
+        );
+        let context = crate::timelog::Context::default();
+        let (content, inserted) = crate::timelog::insert("", &entries, &context).unwrap();
+        assert_eq!(1, inserted.len());
+        let (again, inserted) = crate::timelog::insert(&content, &entries, &context).unwrap();
+        assert!(inserted.is_empty());
+        assert_eq!(content, again);
+    }
+
+    #[test]
+    fn missing_column() {
+        let result = parse("Project,Description,Start date,Start time\n");
+        assert_eq!(
+            Err(FormatError::new(1, "missing column \"end date\"")),
+            result
+        );
+    }
+
+    #[test]
+    fn invalid_time() {
+        let result = parse(indoc::indoc! {"
+            Project,Description,Start date,Start time,End date,End time
+            ABC,,2024-02-13,09:00,2024-02-13,25:00
+        "});
+        assert_eq!(Err(FormatError::new(2, "invalid end date or time")), result);
+    }
+}
@@ -0,0 +1,244 @@
+use super::FormatError;
+use crate::timelog::{Entry, Log, Time};
+use serde::Deserialize;
+use std::{collections::BTreeMap, path::PathBuf};
+
+/// The format to pass to `git log --format` for output that [`parse`] reads: the author date,
+/// the author's email and the subject, separated by tabs.
+pub static LOG_FORMAT: &str = "%aI%x09%ae%x09%s";
+
+/// How entries are suggested from commits, from `[suggest]` in the configuration.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Suggest {
+    /// The most minutes between two commits for them to be part of the same entry.
+    pub gap: u32,
+    /// Minutes of work counted before the first commit of an entry.
+    pub lead: u32,
+    /// The emails of authors whose commits count, defaulting to `user.email` of each repository.
+    pub authors: Vec<String>,
+    /// The project per repository, by path. A leading `~` refers to the home directory.
+    pub repositories: BTreeMap<PathBuf, String>,
+}
+
+impl Default for Suggest {
+    fn default() -> Self {
+        Self {
+            gap: 90,
+            lead: 30,
+            authors: vec![],
+            repositories: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commit {
+    /// When the commit was authored, in the time zone given to [`parse`].
+    pub time: chrono::NaiveDateTime,
+    pub author: String,
+    pub subject: String,
+}
+
+/// Parses the output of `git log` with [`LOG_FORMAT`], converting times to the given time zone.
+pub fn parse<Tz: chrono::TimeZone>(input: &str, tz: &Tz) -> Result<Vec<Commit>, FormatError> {
+    let mut commits = vec![];
+    for (index, line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut fields = line.splitn(3, '\t');
+        let (Some(time), Some(author), subject) = (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(FormatError::new(
+                line_number,
+                "expected a date and an author",
+            ));
+        };
+        let time = chrono::DateTime::parse_from_rfc3339(time)
+            .map_err(|_| FormatError::new(line_number, format!("invalid date \"{}\"", time)))?;
+        commits.push(Commit {
+            time: time.with_timezone(tz).naive_local(),
+            author: author.to_owned(),
+            subject: subject.unwrap_or_default().trim().to_owned(),
+        });
+    }
+    Ok(commits)
+}
+
+/// Suggests entries for a project on a date, from the commits by the given authors. Commits at
+/// most `gap` minutes apart are grouped into an entry, which starts `lead` minutes before the
+/// first commit (but not before midnight) and ends at the last one. The subjects of the commits
+/// become the notes.
+pub fn suggest(
+    commits: &[Commit],
+    date: chrono::NaiveDate,
+    project: &str,
+    authors: &[String],
+    settings: &Suggest,
+) -> Vec<Entry> {
+    let mut commits = commits
+        .iter()
+        .filter(|commit| commit.time.date() == date)
+        .filter(|commit| {
+            authors
+                .iter()
+                .any(|author| author.eq_ignore_ascii_case(&commit.author))
+        })
+        .collect::<Vec<_>>();
+    commits.sort_by_key(|commit| commit.time);
+    let gap = chrono::TimeDelta::minutes(settings.gap.into());
+    let mut clusters: Vec<Vec<&Commit>> = vec![];
+    for commit in commits {
+        match clusters.last_mut() {
+            Some(cluster) if commit.time - cluster.last().unwrap().time <= gap => {
+                cluster.push(commit)
+            }
+            _ => clusters.push(vec![commit]),
+        }
+    }
+    let midnight = date.and_time(chrono::NaiveTime::MIN);
+    clusters
+        .into_iter()
+        .filter_map(|cluster| {
+            let first = super::minute(cluster.first().unwrap().time);
+            let until = super::minute(cluster.last().unwrap().time);
+            let from = (first - chrono::TimeDelta::minutes(settings.lead.into())).max(midnight);
+            let mut subjects = cluster
+                .iter()
+                .map(|commit| commit.subject.as_str())
+                .filter(|subject| !subject.is_empty())
+                .collect::<Vec<_>>();
+            subjects.dedup();
+            let entry = Entry::new(date, Time::Interval { from, until }, project).ok()?;
+            match subjects.is_empty() {
+                true => Some(entry),
+                false => Some(entry.with_notes(subjects.join("; "))),
+            }
+        })
+        .collect()
+}
+
+/// Splits suggested entries into those to insert and those that overlap, in order. An entry is
+/// left out if it overlaps a logged one, or one that's already accepted, as when repositories
+/// for different projects have commits at the same time.
+pub fn accept(entries: Vec<Entry>, logged: &Log) -> (Vec<Entry>, Vec<Entry>) {
+    let mut accepted: Vec<Entry> = vec![];
+    let mut overlapping = vec![];
+    for entry in entries {
+        match logged
+            .iter()
+            .chain(&accepted)
+            .any(|other| other.overlaps(&entry))
+        {
+            true => overlapping.push(entry),
+            false => accepted.push(entry),
+        }
+    }
+    (accepted, overlapping)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> chrono::NaiveDateTime {
+        chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn parse_log() {
+        let input = "\
+2024-02-13T10:15:30+01:00\tme@example.com\tFix the build
+2024-02-13T09:00:00Z\tyou@example.com\t
+";
+        let commits = parse(input, &chrono::Utc).unwrap();
+        assert_eq!(
+            vec![
+                Commit {
+                    time: time("2024-02-13 09:15:30"),
+                    author: "me@example.com".to_owned(),
+                    subject: "Fix the build".to_owned(),
+                },
+                Commit {
+                    time: time("2024-02-13 09:00:00"),
+                    author: "you@example.com".to_owned(),
+                    subject: "".to_owned(),
+                },
+            ],
+            commits
+        );
+        assert_eq!(
+            Err(FormatError::new(1, "invalid date \"yesterday\"")),
+            parse("yesterday\tme@example.com\tFix", &chrono::Utc)
+        );
+        assert_eq!(
+            Err(FormatError::new(2, "expected a date and an author")),
+            parse("\n2024-02-13T10:15:30+01:00", &chrono::Utc)
+        );
+    }
+
+    #[test]
+    fn suggest_clusters() {
+        let commit = |t: &str, author: &str, subject: &str| Commit {
+            time: time(t),
+            author: author.to_owned(),
+            subject: subject.to_owned(),
+        };
+        let commits = [
+            commit("2024-02-13 00:10:00", "me@example.com", "Late fix"),
+            commit("2024-02-13 11:20:59", "ME@example.com", "Add tests"),
+            commit("2024-02-13 10:00:00", "me@example.com", "Start parser"),
+            commit("2024-02-13 12:00:00", "you@example.com", "Review"),
+            commit("2024-02-13 15:30:00", "me@example.com", "Fix parser"),
+            commit("2024-02-14 09:00:00", "me@example.com", "Tomorrow"),
+        ];
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 2, 13).unwrap();
+        let authors = vec!["me@example.com".to_owned()];
+        let entries = suggest(&commits, date, "ABC", &authors, &Suggest::default())
+            .into_iter()
+            .map(|entry| entry.to_markdown())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "00:00 - 00:10: ABC Late fix",
+                "09:30 - 11:20: ABC Start parser; Add tests",
+                "15:00 - 15:30: ABC Fix parser",
+            ],
+            entries
+        );
+
+        let settings = Suggest {
+            gap: 300,
+            lead: 0,
+            ..Suggest::default()
+        };
+        let entries = suggest(&commits, date, "ABC", &authors, &settings);
+        // A single commit without lead time has no duration.
+        assert_eq!(1, entries.len());
+        assert_eq!(time("2024-02-13 10:00:00"), entries[0].from().unwrap());
+        assert_eq!(time("2024-02-13 15:30:00"), entries[0].until().unwrap());
+        assert!(suggest(&commits, date, "ABC", &[], &settings).is_empty());
+    }
+
+    #[test]
+    fn accept_without_overlaps() {
+        let context = crate::timelog::Context::default();
+        let parse = |input: &str| Log::parse(input, &context).unwrap();
+        let logged = parse("## 2024-02-13\n- 9-10 ABC\n");
+        let suggested =
+            parse("## 2024-02-13\n- 9:30-11 ABC\n- 11-12 ABC\n- 11:30-13 DEF\n- 13-14 DEF\n");
+        let (accepted, overlapping) = accept(suggested.into_entries(), &logged);
+        let markdown =
+            |entries: Vec<Entry>| entries.iter().map(Entry::to_markdown).collect::<Vec<_>>();
+        assert_eq!(
+            vec!["11:00 - 12:00: ABC", "13:00 - 14:00: DEF"],
+            markdown(accepted)
+        );
+        assert_eq!(
+            vec!["09:30 - 11:00: ABC", "11:30 - 13:00: DEF"],
+            markdown(overlapping)
+        );
+    }
+}
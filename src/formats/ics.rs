@@ -0,0 +1,427 @@
+use super::FormatError;
+use crate::timelog::{Entry, Log, Time};
+use regex::Regex;
+use std::sync::LazyLock;
+
+static DURATION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\+?P(?:(?<w>\d+)W)?(?:(?<d>\d+)D)?(?:T(?:(?<h>\d+)H)?(?:(?<m>\d+)M)?(?:(?<s>\d+)S)?)?$",
+    )
+    .unwrap()
+});
+
+/// A content line like `DTSTART;TZID=Europe/Amsterdam:20240213T090000`, unfolded.
+struct Property<'a> {
+    line: usize,
+    name: String,
+    params: Vec<&'a str>,
+    value: &'a str,
+}
+
+/// Parses the events in an iCalendar file. The summary becomes the project and the description
+/// becomes notes. Times in UTC are converted to the given time zone, other times are used as is.
+/// All-day events are skipped, as are components within events, like alarms.
+pub fn parse<Tz: chrono::TimeZone>(input: &str, tz: &Tz) -> Result<Vec<Entry>, FormatError> {
+    let lines = unfold(input);
+    let mut entries = vec![];
+    let mut event: Option<Vec<Property>> = None;
+    // How many components within the event the current line is in.
+    let mut nested = 0;
+    for (line, content) in lines.iter() {
+        let property = parse_property(*line, content)?;
+        match (property.name.as_str(), property.value, &mut event) {
+            ("BEGIN", "VEVENT", None) => event = Some(vec![]),
+            ("BEGIN", _, Some(_)) => nested += 1,
+            ("END", _, Some(_)) if nested > 0 => nested -= 1,
+            ("END", "VEVENT", Some(_)) => {
+                if let Some(entry) = parse_event(&event.take().unwrap(), *line, tz)? {
+                    entries.push(entry);
+                }
+            }
+            (_, _, Some(properties)) if nested == 0 => properties.push(property),
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
+
+/// Joins lines that were folded by starting the next line with a space or tab.
+fn unfold(input: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = vec![];
+    for (index, line) in input.lines().enumerate() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continued), Some((_, previous))) => previous.push_str(continued),
+            _ => lines.push((index + 1, line.to_owned())),
+        }
+    }
+    lines
+}
+
+fn parse_property(line: usize, content: &str) -> Result<Property<'_>, FormatError> {
+    let mut quoted = false;
+    let colon = content
+        .char_indices()
+        .find(|(_, c)| {
+            if *c == '"' {
+                quoted = !quoted;
+            }
+            *c == ':' && !quoted
+        })
+        .map(|(index, _)| index)
+        .ok_or_else(|| FormatError::new(line, "expected \":\""))?;
+    let mut params = content[..colon].split(';');
+    Ok(Property {
+        line,
+        name: params.next().unwrap_or_default().to_ascii_uppercase(),
+        params: params.collect(),
+        value: &content[colon + 1..],
+    })
+}
+
+fn parse_event<Tz: chrono::TimeZone>(
+    properties: &[Property],
+    line: usize,
+    tz: &Tz,
+) -> Result<Option<Entry>, FormatError> {
+    let property = |name: &str| properties.iter().find(|p| p.name == name);
+    let Some(start) = property("DTSTART") else {
+        return Err(FormatError::new(line, "event without DTSTART"));
+    };
+    let Some(from) = datetime(start, tz)? else {
+        return Ok(None);
+    };
+    let until = match (property("DTEND"), property("DURATION")) {
+        (Some(end), _) => datetime(end, tz)?,
+        (None, Some(duration)) => parse_duration(duration.value).map(|d| from + d),
+        (None, None) => None,
+    };
+    let Some(until) = until.filter(|until| from < *until) else {
+        return Err(FormatError::new(start.line, "event without a valid end"));
+    };
+    let Some(time) = super::interval(from, until) else {
+        return Ok(None);
+    };
+    Ok(Some(Entry {
+        date: from.date(),
+        time,
+        project: super::project(&property("SUMMARY").map_or(String::new(), |p| unescape(p.value))),
+        notes: property("DESCRIPTION").and_then(|p| super::notes(&unescape(p.value))),
+    }))
+}
+
+/// Parses a date-time property, or `None` for dates without a time.
+fn datetime<Tz: chrono::TimeZone>(
+    property: &Property,
+    tz: &Tz,
+) -> Result<Option<chrono::NaiveDateTime>, FormatError> {
+    let value = property.value.trim();
+    if property
+        .params
+        .iter()
+        .any(|p| p.eq_ignore_ascii_case("VALUE=DATE"))
+        || value.len() == 8
+    {
+        return Ok(None);
+    }
+    let invalid = || FormatError::new(property.line, format!("invalid date-time \"{}\"", value));
+    match value.strip_suffix('Z') {
+        Some(utc) => chrono::NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+            .map(|utc| Some(utc.and_utc().with_timezone(tz).naive_local()))
+            .map_err(|_| invalid()),
+        None => chrono::NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+            .map(Some)
+            .map_err(|_| invalid()),
+    }
+}
+
+fn parse_duration(s: &str) -> Option<chrono::TimeDelta> {
+    let cap = DURATION_RE.captures(s.trim())?;
+    let part = |name: &str| {
+        cap.name(name)
+            .map_or(Some(0), |m| m.as_str().parse::<i64>().ok())
+    };
+    let seconds = part("w")? * 604800 + part("d")? * 86400 + part("h")? * 3600 + part("m")? * 60;
+    chrono::TimeDelta::try_seconds(seconds + part("s")?)
+}
+
+fn unescape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => result.push('\n'),
+            Some(c) => result.push(c),
+            None => {}
+        }
+    }
+    result
+}
+
+/// Writes an iCalendar file with an event for each entry, with the project as summary and notes
+/// as description. Times are written as floating (local) times, and entries without clock times
+/// become all-day events. Event UIDs are derived from the date, time and project, so exporting the
+/// same entries again updates events in calendar apps instead of duplicating them.
+pub fn write(log: &Log, stamp: chrono::DateTime<chrono::Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        format!("PRODID:-//myw//myw {}//EN", env!("CARGO_PKG_VERSION")),
+    ];
+    for entry in log.0.iter() {
+        lines.push("BEGIN:VEVENT".to_owned());
+        lines.push(format!("UID:{}", uid(entry)));
+        lines.push(format!("DTSTAMP:{}", stamp.format("%Y%m%dT%H%M%SZ")));
+        match entry.time {
+            Time::Interval { from, until } => {
+                lines.push(format!("DTSTART:{}", from.format(DATETIME_FORMAT)));
+                lines.push(format!("DTEND:{}", until.format(DATETIME_FORMAT)));
+                lines.push(format!("SUMMARY:{}", escape(&entry.project)));
+            }
+            Time::Duration(duration) => {
+                let end = entry.date + chrono::Days::new(1);
+                lines.push(format!(
+                    "DTSTART;VALUE=DATE:{}",
+                    entry.date.format(DATE_FORMAT)
+                ));
+                lines.push(format!("DTEND;VALUE=DATE:{}", end.format(DATE_FORMAT)));
+                lines.push(format!(
+                    "SUMMARY:{} ({})",
+                    escape(&entry.project),
+                    Time::Duration(duration)
+                ));
+            }
+            Time::AllDay => {
+                let end = entry.date + chrono::Days::new(1);
+                lines.push(format!(
+                    "DTSTART;VALUE=DATE:{}",
+                    entry.date.format(DATE_FORMAT)
+                ));
+                lines.push(format!("DTEND;VALUE=DATE:{}", end.format(DATE_FORMAT)));
+                lines.push(format!("SUMMARY:{}", escape(&entry.project)));
+            }
+        }
+        if let Some(notes) = &entry.notes {
+            lines.push(format!("DESCRIPTION:{}", escape(notes)));
+        }
+        lines.push("END:VEVENT".to_owned());
+    }
+    lines.push("END:VCALENDAR".to_owned());
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+static DATE_FORMAT: &str = "%Y%m%d";
+static DATETIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+fn uid(entry: &Entry) -> String {
+    let time = match entry.time {
+        Time::Interval { from, until } => {
+            format!("{}-{}", from.format("%H%M"), until.format("%H%M"))
+        }
+        Time::Duration(duration) => format!("{}m", duration.num_minutes()),
+        Time::AllDay => "allday".to_owned(),
+    };
+    format!(
+        "{}-{}-{:016x}@myw",
+        entry.date.format(DATE_FORMAT),
+        time,
+        fnv1a(entry.project.as_bytes())
+    )
+}
+
+/// A hash that, unlike `std::hash`, is guaranteed to stay the same across versions and platforms.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Splits a content line into lines of at most 75 bytes, each ending in CRLF.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn parse_events() {
+        let tz = chrono::FixedOffset::east_opt(3600).unwrap();
+        let input = indoc::indoc! {r#"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            BEGIN:VEVENT
+            UID:1
+            DTSTART:20240213T080000Z
+            DTEND:20240213T090000Z
+            SUMMARY:ABC
+            DESCRIPTION:Standup\, planning and a long description that has been fol
+             ded
+            END:VEVENT
+            BEGIN:VEVENT
+            DTSTART;TZID="Europe/Amsterdam":20240213T100000
+            DURATION:PT1H30M
+            SUMMARY:Some project
+            END:VEVENT
+            BEGIN:VEVENT
+            DTSTART;VALUE=DATE:20240214
+            SUMMARY:Holiday
+            END:VEVENT
+            END:VCALENDAR
+        "#}
+        .replace('\n', "\r\n");
+        let result = parse(&input, &tz).unwrap();
+        let expected = vec![
+            Entry::parse("9-10 ABC", &date(2024, 2, 13)).unwrap(),
+            Entry::parse("10-1130 \"Some project\"", &date(2024, 2, 13)).unwrap(),
+        ];
+        assert_eq!(expected, result);
+        assert_eq!(
+            Some("Standup, planning and a long description that has been folded".into()),
+            result[0].notes
+        );
+    }
+
+    #[test]
+    fn parse_with_alarm() {
+        let input = indoc::indoc! {"
+            BEGIN:VEVENT
+            DTSTART:20240213T090000
+            DTEND:20240213T100000
+            SUMMARY:ABC
+            BEGIN:VALARM
+            ACTION:DISPLAY
+            DESCRIPTION:This is an event reminder
+            TRIGGER:-PT10M
+            DURATION:PT15M
+            END:VALARM
+            END:VEVENT
+            BEGIN:VEVENT
+            DTSTART:20240213T110000
+            DURATION:PT30M
+            SUMMARY:DEF
+            BEGIN:VALARM
+            TRIGGER:-PT10M
+            DURATION:PT2H
+            END:VALARM
+            END:VEVENT
+        "};
+        let result = parse(input, &chrono::Utc).unwrap();
+        let expected = vec![
+            Entry::parse("9-10 ABC", &date(2024, 2, 13)).unwrap(),
+            Entry::parse("11-1130 DEF", &date(2024, 2, 13)).unwrap(),
+        ];
+        assert_eq!(expected, result);
+        assert_eq!(None, result[0].notes);
+    }
+
+    #[test]
+    fn parse_without_end() {
+        let input = indoc::indoc! {"
+            BEGIN:VEVENT
+            DTSTART:20240213T080000
+            SUMMARY:ABC
+            END:VEVENT
+        "};
+        let result = parse(input, &chrono::Utc);
+        assert_eq!(
+            Err(FormatError::new(2, "event without a valid end")),
+            result
+        );
+    }
+
+    #[test]
+    fn unescape_text() {
+        assert_eq!("a, b; c\nd\\e", unescape(r"a\, b\; c\nd\\e"));
+    }
+
+    fn stamp() -> chrono::DateTime<chrono::Utc> {
+        date(2024, 2, 16).and_hms_opt(12, 0, 0).unwrap().and_utc()
+    }
+
+    #[test]
+    fn write_events() {
+        let log = Log(vec![
+            Entry::parse("9-10 ABC Standup, planning", &date(2024, 2, 13)).unwrap(),
+            Entry::parse("1h30 DEF", &date(2024, 2, 13)).unwrap(),
+        ]);
+        let result = write(&log, stamp());
+        let expected = indoc::indoc! {"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//myw//myw 0.1.0//EN
+            BEGIN:VEVENT
+            UID:20240213-0900-1000-fa2fe219a07442eb@myw
+            DTSTAMP:20240216T120000Z
+            DTSTART:20240213T090000
+            DTEND:20240213T100000
+            SUMMARY:ABC
+            DESCRIPTION:Standup\\, planning
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:20240213-90m-e0365a1991be55ac@myw
+            DTSTAMP:20240216T120000Z
+            DTSTART;VALUE=DATE:20240213
+            DTEND;VALUE=DATE:20240214
+            SUMMARY:DEF (1h30)
+            END:VEVENT
+            END:VCALENDAR
+        "}
+        .replace('\n', "\r\n");
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let log = Log(vec![
+            Entry::parse("9-10 \"Some; project\" with, notes", &date(2024, 2, 13)).unwrap(),
+            Entry::parse("23:00 +2h ABC", &date(2024, 2, 13)).unwrap(),
+        ]);
+        let result = parse(&write(&log, stamp()), &chrono::Utc).unwrap();
+        assert_eq!(log.0, result);
+        assert_eq!(log.0[0].notes, result[0].notes);
+    }
+
+    #[test]
+    fn uid_is_stable() {
+        let a = Entry::parse("9-10 ABC some notes", &date(2024, 2, 13)).unwrap();
+        let b = Entry::parse("09:00 - 10:00: ABC other notes", &date(2024, 2, 13)).unwrap();
+        let c = Entry::parse("9-10 DEF", &date(2024, 2, 13)).unwrap();
+        assert_eq!(uid(&a), uid(&b));
+        assert_ne!(uid(&a), uid(&c));
+    }
+
+    #[test]
+    fn fold_long_lines() {
+        let line = format!("DESCRIPTION:{}", "é".repeat(40));
+        let result = fold(&line);
+        assert!(result.split("\r\n").all(|line| line.len() <= 75));
+        assert_eq!(line, unfold(&result)[0].1);
+    }
+}
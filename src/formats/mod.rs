@@ -0,0 +1,63 @@
+//! Conversion between `timelog` entries and the formats of other time trackers.
+
+pub mod csv;
+pub mod git;
+pub mod ics;
+pub mod timeclock;
+pub mod timewarrior;
+
+use crate::timelog::Time;
+use std::{error::Error, fmt};
+
+/// The project used for imported time that isn't assigned to a project.
+pub const NO_PROJECT: &str = "No project";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl FormatError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl Error for FormatError {}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+fn project(name: &str) -> String {
+    match name.trim() {
+        "" => NO_PROJECT.to_owned(),
+        name => name.to_owned(),
+    }
+}
+
+/// An interval in whole minutes, which is all logs can express, so that importing it again matches
+/// what was written before. Intervals within a minute are `None`.
+fn interval(from: chrono::NaiveDateTime, until: chrono::NaiveDateTime) -> Option<Time> {
+    let (from, until) = (minute(from), minute(until));
+    (from < until).then_some(Time::Interval { from, until })
+}
+
+/// Drops the seconds of a time.
+fn minute(time: chrono::NaiveDateTime) -> chrono::NaiveDateTime {
+    chrono::Timelike::with_second(&time, 0)
+        .and_then(|time| chrono::Timelike::with_nanosecond(&time, 0))
+        .unwrap_or(time)
+}
+
+fn notes(notes: &str) -> Option<String> {
+    Some(notes.trim())
+        .filter(|notes| !notes.is_empty())
+        .map(Into::into)
+}
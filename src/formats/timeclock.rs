@@ -0,0 +1,72 @@
+use crate::timelog::{Log, Time};
+
+/// Writes entries as clock-in and clock-out lines in the timeclock format used by `hledger` and
+/// `ledger`, with the project as account and the notes as description. Hashtags in the notes
+/// become tags in a comment. Entries without clock times are written as comments, since timeclock
+/// has no way to express them.
+pub fn write(log: &Log) -> String {
+    let mut output = String::new();
+    for entry in log.0.iter() {
+        let account = entry
+            .project
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let Time::Interval { from, until } = entry.time else {
+            output.push_str(&format!(
+                "; {} {} {} (no clock times)\n",
+                entry.date.format("%Y/%m/%d"),
+                account,
+                entry.time
+            ));
+            continue;
+        };
+        output.push_str(&format!("i {} {}", from.format(DATETIME_FORMAT), account));
+        if let Some(notes) = &entry.notes {
+            output.push_str(&format!("  {}", notes.replace(';', ",")));
+        }
+        let tags = entry.tags();
+        if !tags.is_empty() {
+            let tags = tags.iter().map(|tag| format!("{}:", tag));
+            output.push_str(&format!("  ; {}", tags.collect::<Vec<_>>().join(", ")));
+        }
+        output.push_str(&format!("\no {}\n", until.format(DATETIME_FORMAT)));
+    }
+    output
+}
+
+static DATETIME_FORMAT: &str = "%Y/%m/%d %H:%M:%S";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timelog::Entry;
+
+    fn date(y: i32, m: u32, d: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn write_entries() {
+        let log = Log(vec![
+            Entry::parse("9-10 ABC", &date(2024, 2, 13)).unwrap(),
+            Entry::parse(
+                "10-1130 \"Some  project\" fixed #bug; #review",
+                &date(2024, 2, 13),
+            )
+            .unwrap(),
+            Entry::parse("23:00 +2h DEF", &date(2024, 2, 13)).unwrap(),
+            Entry::parse("1h30 GHI", &date(2024, 2, 14)).unwrap(),
+        ]);
+        let expected = indoc::indoc! {"
+            i 2024/02/13 09:00:00 ABC
+            o 2024/02/13 10:00:00
+            i 2024/02/13 10:00:00 Some project  fixed #bug, #review  ; bug:, review:
+            o 2024/02/13 11:30:00
+            i 2024/02/13 23:00:00 DEF
+            o 2024/02/14 01:00:00
+            ; 2024/02/14 GHI 1h30 (no clock times)
+        "};
+        assert_eq!(expected, write(&log));
+    }
+}
@@ -0,0 +1,253 @@
+use super::FormatError;
+use crate::timelog::{Entry, Log, Time};
+
+static TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Parses timewarrior's data files (like `~/.timewarrior/data/2024-02.data`), which store
+/// intervals in UTC. The first tag becomes the project, and any other tags and the annotation
+/// become notes. Intervals that are still open are skipped.
+pub fn parse<Tz: chrono::TimeZone>(input: &str, tz: &Tz) -> Result<Vec<Entry>, FormatError> {
+    let mut entries = vec![];
+    for (index, line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some(line) = line.strip_prefix("inc ") else {
+            return Err(FormatError::new(line_number, "expected \"inc\""));
+        };
+        let (range, rest) = line.split_once(" #").unwrap_or((line, ""));
+        let Some((from, until)) = range.split_once(" - ") else {
+            continue;
+        };
+        let time = |s: &str| {
+            chrono::NaiveDateTime::parse_from_str(s.trim(), TIME_FORMAT)
+                .map(|utc| utc.and_utc().with_timezone(tz).naive_local())
+                .map_err(|_| FormatError::new(line_number, format!("invalid time \"{}\"", s)))
+        };
+        let (from, until) = (time(from)?, time(until)?);
+        let (tags, annotation) = rest.split_once(" #").unwrap_or((rest, ""));
+        let mut tags = words(tags).into_iter();
+        let project = super::project(&tags.next().unwrap_or_default());
+        let notes = tags.chain(words(annotation)).collect::<Vec<_>>().join(" ");
+        let Some(time) = super::interval(from, until) else {
+            continue;
+        };
+        entries.push(Entry {
+            date: from.date(),
+            time,
+            project,
+            notes: super::notes(&notes),
+        });
+    }
+    Ok(entries)
+}
+
+/// Splits tags into words, where quoted tags may contain spaces and escaped quotes.
+pub(super) fn words(s: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut chars = s.trim().chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' => continue,
+            '"' => {
+                let mut word = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => word.extend(chars.next()),
+                        '"' => break,
+                        c => word.push(c),
+                    }
+                }
+                words.push(word);
+            }
+            c => {
+                let mut word = String::from(c);
+                while let Some(c) = chars.next_if(|c| *c != ' ') {
+                    word.push(c);
+                }
+                words.push(word);
+            }
+        }
+    }
+    words
+}
+
+/// Writes entries as intervals in timewarrior's data format, converting times from the given time
+/// zone to UTC. The project and any hashtags in the notes become tags, and the notes become the
+/// annotation. Entries that can't be written are left out, see [`unexportable`].
+pub fn write<Tz: chrono::TimeZone>(log: &Log, tz: &Tz) -> String {
+    let mut output = String::new();
+    for entry in log.0.iter() {
+        let Some((from, until)) = utc(entry, tz) else {
+            continue;
+        };
+        let mut tags = vec![quote(&entry.project)];
+        tags.extend(entry.tags().into_iter().map(quote));
+        output.push_str(&format!("inc {} - {} # {}", from, until, tags.join(" ")));
+        if let Some(notes) = &entry.notes {
+            output.push_str(&format!(" # {}", quote(notes)));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// The number of entries [`write`] leaves out: those without clock times, since intervals need a
+/// start and end, and those with a time that doesn't exist in the time zone, like in the hour
+/// skipped when clocks are set forward.
+pub fn unexportable<Tz: chrono::TimeZone>(log: &Log, tz: &Tz) -> usize {
+    log.0
+        .iter()
+        .filter(|entry| utc(entry, tz).is_none())
+        .count()
+}
+
+/// The start and end of an entry in UTC, formatted for timewarrior.
+fn utc<Tz: chrono::TimeZone>(entry: &Entry, tz: &Tz) -> Option<(String, String)> {
+    let Time::Interval { from, until } = entry.time else {
+        return None;
+    };
+    let utc = |time: chrono::NaiveDateTime| {
+        let local = tz.from_local_datetime(&time).earliest()?;
+        Some(local.naive_utc().format(TIME_FORMAT).to_string())
+    };
+    Some((utc(from)?, utc(until)?))
+}
+
+/// Quotes words with spaces or quotes in them, as timewarrior does for tags.
+fn quote(word: &str) -> String {
+    if word.contains([' ', '"', '#']) {
+        format!("\"{}\"", word.replace('"', "\\\""))
+    } else {
+        word.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn parse_intervals() {
+        let tz = chrono::FixedOffset::east_opt(3600).unwrap();
+        let result = parse(
+            indoc::indoc! {r#"
+                inc 20240213T080000Z - 20240213T090000Z # ABC
+                inc 20240213T090000Z - 20240213T093000Z # "Some project" review "with \"quotes\"" # "an annotation"
+                inc 20240213T100000Z - 20240213T110000Z
+                inc 20240213T120000Z # ABC
+            "#},
+            &tz,
+        );
+        let expected = vec![
+            Entry::parse("9-10 ABC", &date(2024, 2, 13)).unwrap(),
+            Entry::parse("10-1030 \"Some project\"", &date(2024, 2, 13)).unwrap(),
+            Entry::parse("11-12 \"No project\"", &date(2024, 2, 13)).unwrap(),
+        ];
+        let result = result.unwrap();
+        assert_eq!(expected, result);
+        assert_eq!(
+            Some("review with \"quotes\" an annotation".into()),
+            result[1].notes
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let tz = chrono::Utc;
+        let result = parse("inc 20240213T080000 - 20240213T090000Z # ABC", &tz);
+        assert_eq!(
+            Err(FormatError::new(1, "invalid time \"20240213T080000\"")),
+            result
+        );
+        let result = parse("exc something", &tz);
+        assert_eq!(Err(FormatError::new(1, "expected \"inc\"")), result);
+    }
+
+    #[test]
+    fn write_intervals() {
+        let tz = chrono::FixedOffset::east_opt(3600).unwrap();
+        let log = Log(vec![
+            Entry::parse("9-10 ABC", &date(2024, 2, 13)).unwrap(),
+            Entry::parse("10-1030 \"Some project\" fixed #bug", &date(2024, 2, 13)).unwrap(),
+            Entry::parse("1h30 GHI", &date(2024, 2, 14)).unwrap(),
+        ]);
+        let expected = indoc::indoc! {r#"
+            inc 20240213T080000Z - 20240213T090000Z # ABC
+            inc 20240213T090000Z - 20240213T093000Z # "Some project" bug # "fixed #bug"
+        "#};
+        let result = write(&log, &tz);
+        assert_eq!(expected, result);
+        assert_eq!(log.0[..2], parse(&result, &tz).unwrap());
+    }
+
+    /// Central European time around the switch to summer time, when 02:00 to 03:00 is skipped.
+    #[derive(Clone, Copy, Debug)]
+    struct Cet;
+
+    impl chrono::TimeZone for Cet {
+        type Offset = chrono::FixedOffset;
+
+        fn from_offset(_: &Self::Offset) -> Self {
+            Cet
+        }
+
+        fn offset_from_local_date(
+            &self,
+            _: &chrono::NaiveDate,
+        ) -> chrono::LocalResult<Self::Offset> {
+            chrono::LocalResult::Single(Self::offset(1))
+        }
+
+        fn offset_from_local_datetime(
+            &self,
+            local: &chrono::NaiveDateTime,
+        ) -> chrono::LocalResult<Self::Offset> {
+            let switch = date(2024, 3, 31).and_hms_opt(2, 0, 0).unwrap();
+            match local {
+                local if *local < switch => chrono::LocalResult::Single(Self::offset(1)),
+                local if *local < switch + chrono::TimeDelta::hours(1) => chrono::LocalResult::None,
+                _ => chrono::LocalResult::Single(Self::offset(2)),
+            }
+        }
+
+        fn offset_from_utc_date(&self, _: &chrono::NaiveDate) -> Self::Offset {
+            Self::offset(1)
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &chrono::NaiveDateTime) -> Self::Offset {
+            match *utc < date(2024, 3, 31).and_hms_opt(1, 0, 0).unwrap() {
+                true => Self::offset(1),
+                false => Self::offset(2),
+            }
+        }
+    }
+
+    impl Cet {
+        fn offset(hours: i32) -> chrono::FixedOffset {
+            chrono::FixedOffset::east_opt(hours * 3600).unwrap()
+        }
+    }
+
+    #[test]
+    fn write_around_switch_to_summer_time() {
+        let log = Log(vec![
+            Entry::parse("1-1:30 ABC", &date(2024, 3, 31)).unwrap(),
+            Entry::parse("1:30-2:30 ABC", &date(2024, 3, 31)).unwrap(),
+            Entry::parse("3-4 ABC", &date(2024, 3, 31)).unwrap(),
+            Entry::parse("1h DEF", &date(2024, 3, 31)).unwrap(),
+        ]);
+        let expected = indoc::indoc! {r#"
+            inc 20240331T000000Z - 20240331T003000Z # ABC
+            inc 20240331T010000Z - 20240331T020000Z # ABC
+        "#};
+        assert_eq!(expected, write(&log, &Cet));
+        assert_eq!(2, unexportable(&log, &Cet));
+    }
+}
@@ -1,3 +1,46 @@
-pub mod report;
-pub mod timelog;
-pub mod utils;
+//! Reports on time logged in markdown files.
+//!
+//! A [`Log`] is parsed from markdown, or collected from [`Entry`] values, and summed up in
+//! [`Report`]s:
+//!
+//! ```
+//! use myw::{Context, Entry, Log, Report, Text, Render};
+//!
+//! let log = Log::parse("## 2024-02-13\n- 9-10:30 ABC\n", &Context::default())?;
+//! let date = chrono::NaiveDate::from_ymd_opt(2024, 2, 14).unwrap();
+//! let entry = Entry::builder("DEF")
+//!     .on(date)
+//!     .lasting(chrono::TimeDelta::minutes(45))
+//!     .build()?;
+//! let log: Log = log.into_iter().chain([entry]).collect();
+//! assert_eq!(1, log.for_project("DEF").len());
+//!
+//! let reports = Report::builder(&log).days(false).build();
+//! assert_eq!("Total", reports.last().unwrap().title);
+//! println!("{}", Text.document(&reports));
+//! # Ok::<(), myw::Error>(())
+//! ```
+//!
+//! Everything here is the supported API, checked with `cargo semver-checks` in CI. The rest of
+//! the crate is private to the `myw` command line tool, which runs through [`main`].
+
+mod commands;
+mod config;
+mod error;
+mod formats;
+mod report;
+mod timelog;
+mod utils;
+
+pub use commands::main;
+pub use config::{Config, ConfigError};
+pub use error::Error;
+pub use formats::FormatError;
+pub use report::invoice::InvoiceError;
+pub use report::{Html, Markdown, Render, Report, ReportBuilder, Sum, Target, Text};
+#[cfg(feature = "cache")]
+pub use timelog::Cache;
+pub use timelog::{
+    Context, Entry, EntryBuilder, HeadingFormats, Locale, Log, Reader, Time, Warning,
+};
+pub use utils::{DateRange, ParseDateRangeError};
@@ -0,0 +1,192 @@
+use super::{format_hours, targets::duration, Report, Sum};
+use crate::timelog::{Entry, Log, Time};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// How breaks are handled, from the `[breaks]` table in the configuration.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Breaks {
+    /// Projects that are breaks rather than work, like `LUNCH`, compared case-insensitively.
+    pub projects: Vec<String>,
+    pub deductions: Vec<Deduction>,
+}
+
+/// A break that has to be taken on long days, which is deducted from the time worked on days
+/// without enough logged breaks.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Deduction {
+    /// The hours worked on a day above which the break applies.
+    pub after: Decimal,
+    pub minutes: u32,
+}
+
+impl Breaks {
+    pub fn is_empty(&self) -> bool {
+        self.projects.is_empty() && self.deductions.is_empty()
+    }
+
+    pub fn is_break(&self, entry: &Entry) -> bool {
+        self.projects
+            .iter()
+            .any(|project| project.eq_ignore_ascii_case(&entry.project))
+    }
+
+    /// Splits a log into work and breaks.
+    pub fn split(&self, log: &Log) -> (Log, Log) {
+        let (breaks, work) = log
+            .0
+            .iter()
+            .cloned()
+            .partition(|entry| self.is_break(entry));
+        (Log(work), Log(breaks))
+    }
+
+    /// The time to deduct from a day's work for breaks that weren't logged: the largest shortfall
+    /// of the breaks that apply.
+    pub fn deduction(
+        &self,
+        worked: chrono::TimeDelta,
+        breaks: chrono::TimeDelta,
+    ) -> chrono::TimeDelta {
+        self.deductions
+            .iter()
+            .filter(|deduction| worked > duration(deduction.after))
+            .map(|deduction| chrono::TimeDelta::minutes(deduction.minutes.into()) - breaks)
+            .max()
+            .unwrap_or_default()
+            .max(chrono::TimeDelta::zero())
+    }
+
+    /// The time deducted per day for breaks that weren't logged, for days with a deduction.
+    pub fn deductions(&self, log: &Log) -> BTreeMap<chrono::NaiveDate, chrono::TimeDelta> {
+        if self.deductions.is_empty() {
+            return BTreeMap::new();
+        }
+        log.by_date()
+            .into_iter()
+            .filter_map(|(date, log)| {
+                let (work, breaks) = self.split(&log);
+                let deduction = self.deduction(work.sum_duration(), breaks.sum_duration());
+                (deduction > chrono::TimeDelta::zero()).then_some((date, deduction))
+            })
+            .collect()
+    }
+}
+
+impl Report {
+    /// The time between the first and last entry of each day, the breaks in between and the time
+    /// worked, for checking against working time regulations.
+    pub(crate) fn working_hours(log: &Log, breaks: &Breaks) -> Self {
+        let entries = log
+            .by_date()
+            .into_iter()
+            .filter(|(_, log)| log.0.iter().any(|entry| entry.time != Time::AllDay))
+            .map(|(date, log)| {
+                let (work, logged) = breaks.split(&log);
+                let deducted = breaks.deduction(work.sum_duration(), logged.sum_duration());
+                let worked = work.sum_duration() - deducted;
+                let from = log.0.iter().filter_map(Entry::from).min();
+                let until = log.0.iter().filter_map(Entry::until).max();
+                let mut name = date.to_string();
+                if let (Some(from), Some(until)) = (from, until) {
+                    name.push_str(&format!(
+                        " {} - {} ({})",
+                        from.format("%H:%M"),
+                        until.format("%H:%M"),
+                        format_hours(&(until - from))
+                    ));
+                }
+                name.push_str(&format!(
+                    ", breaks {}",
+                    format_hours(&(logged.sum_duration() + deducted))
+                ));
+                if deducted > chrono::TimeDelta::zero() {
+                    name.push_str(&format!(" ({} deducted)", format_hours(&deducted)));
+                }
+                (name, Sum::from(worked))
+            })
+            .collect();
+        Self {
+            title: "Working hours".to_owned(),
+            entries: Some(entries),
+            total: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timelog::Context;
+
+    fn parse(input: &str) -> Log {
+        Log::parse(input, &Context::default()).unwrap()
+    }
+
+    fn breaks() -> Breaks {
+        Breaks {
+            projects: vec!["LUNCH".to_owned()],
+            deductions: vec![
+                Deduction {
+                    after: Decimal::from(6),
+                    minutes: 30,
+                },
+                Deduction {
+                    after: Decimal::from(9),
+                    minutes: 45,
+                },
+            ],
+        }
+    }
+
+    fn minutes(minutes: i64) -> chrono::TimeDelta {
+        chrono::TimeDelta::minutes(minutes)
+    }
+
+    #[test]
+    fn deduction() {
+        let breaks = breaks();
+        assert_eq!(minutes(0), breaks.deduction(minutes(360), minutes(0)));
+        assert_eq!(minutes(30), breaks.deduction(minutes(361), minutes(0)));
+        assert_eq!(minutes(10), breaks.deduction(minutes(400), minutes(20)));
+        assert_eq!(minutes(0), breaks.deduction(minutes(400), minutes(30)));
+        assert_eq!(minutes(15), breaks.deduction(minutes(600), minutes(30)));
+    }
+
+    #[test]
+    fn deductions() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13
+            - 9-12 ABC
+            - 12-12:30 lunch
+            - 12:30-17 ABC
+            ## 2024-02-14
+            - 9-17 ABC
+        "});
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 2, 14).unwrap();
+        assert_eq!(
+            BTreeMap::from([(date, minutes(30))]),
+            breaks().deductions(&log)
+        );
+        assert!(Breaks::default().deductions(&log).is_empty());
+    }
+
+    #[test]
+    fn working_hours() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13
+            - 9-12 ABC
+            - 12-12:15 LUNCH
+            - 12:15-17 ABC
+        "});
+        let report = Report::working_hours(&log, &breaks());
+        let expected = vec![(
+            "2024-02-13 09:00 - 17:00 (8), breaks 0.5 (0.25 deducted)".to_owned(),
+            minutes(450).into(),
+        )];
+        assert_eq!(Some(expected), report.entries);
+    }
+}
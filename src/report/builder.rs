@@ -0,0 +1,268 @@
+use super::{Fill, Report};
+use crate::config::Config;
+use crate::timelog::Log;
+use crate::utils::DateRange;
+
+/// Builds the reports `myw report` prints for a log: per day, per week if there's a weekly
+/// target, per project, breaks and working hours if breaks are configured, budgets, and the
+/// total.
+pub struct ReportBuilder<'a> {
+    log: &'a Log,
+    config: Option<&'a Config>,
+    range: Option<DateRange>,
+    budgets: Option<&'a Log>,
+    days: bool,
+}
+
+impl<'a> ReportBuilder<'a> {
+    pub(super) fn new(log: &'a Log) -> Self {
+        Self {
+            log,
+            config: None,
+            range: None,
+            budgets: None,
+            days: true,
+        }
+    }
+
+    /// The rounding, targets, calendar and breaks to report with.
+    pub fn config(self, config: &'a Config) -> Self {
+        Self {
+            config: Some(config),
+            ..self
+        }
+    }
+
+    /// The dates the log covers. With targets, days in the range without entries count against
+    /// them, and days with entries after it, like planned ones, don't.
+    pub fn range(self, range: DateRange) -> Self {
+        Self {
+            range: Some(range),
+            ..self
+        }
+    }
+
+    /// The log that budgets are spent in, which should cover their ranges. Without it there's no
+    /// budgets report.
+    pub fn budgets(self, log: &'a Log) -> Self {
+        Self {
+            budgets: Some(log),
+            ..self
+        }
+    }
+
+    /// Whether to start with a report per day, which is the default.
+    pub fn days(self, days: bool) -> Self {
+        Self { days, ..self }
+    }
+
+    pub fn build(self) -> Vec<Report> {
+        let default = Config::default();
+        let config = self.config.unwrap_or(&default);
+        // Breaks don't count as work, and breaks that should have been taken are deducted from
+        // the totals, but not from the time per project.
+        let (log, _) = config.breaks.split(self.log);
+        let deductions = config.breaks.deductions(self.log);
+        let rounding = config.rounding.as_ref();
+        let targets = &config.targets;
+        let fill = match self.range {
+            Some(range)
+                if range.0 <= range.1 && (targets.daily.is_some() || targets.weekly.is_some()) =>
+            {
+                Fill::Within(range)
+            }
+            _ => Fill::Padded,
+        };
+        let mut calendar = config.calendar.clone();
+        calendar.add_leave(self.log);
+        let mut reports = vec![];
+        if self.days {
+            let mut days = Report::days(&log, fill.clone(), rounding);
+            for (date, report) in days.iter_mut() {
+                if let (Some(total), Some(deduction)) = (&mut report.total, deductions.get(date)) {
+                    total.deduct(*deduction);
+                }
+            }
+            if let Some(daily) = targets.daily() {
+                let start = days.partition_point(|(date, _)| !fill.counts(date));
+                let end = start + days[start..].partition_point(|(date, _)| fill.counts(date));
+                Report::track(&mut days[start..end], daily, &calendar);
+            }
+            calendar.annotate(&mut days);
+            reports.extend(days.into_iter().map(|(_, report)| report));
+        }
+        if let Some(weekly) = targets.weekly() {
+            reports.push(Report::by_week(
+                &log,
+                fill,
+                rounding,
+                Some(weekly),
+                &calendar,
+                &deductions,
+            ));
+        }
+        reports.push(Report::by_project(&log, rounding));
+        if !config.breaks.is_empty() {
+            let (_, breaks) = config.breaks.split(self.log);
+            if !breaks.is_empty() {
+                reports.push(Report::by_project(&breaks, None).with_title("Breaks"));
+            }
+            reports.push(Report::working_hours(self.log, &config.breaks));
+        }
+        if let Some(budgets) = self.budgets.filter(|_| !config.budgets.is_empty()) {
+            reports.push(Report::budgets(&config.budgets, budgets, rounding));
+        }
+        let mut total = Report::total(&log, rounding);
+        if let Some(total) = total.total.as_mut() {
+            total.deduct(deductions.values().sum());
+        }
+        reports.push(total);
+        reports
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timelog::Context;
+
+    #[test]
+    fn planned_days() {
+        let log = Log::parse(
+            indoc::indoc! {"
+                ## 2024-02-12
+                - 9-17 ABC
+                ## 2024-02-15
+                - 9-12 ABC
+            "},
+            &Context::default(),
+        )
+        .unwrap();
+        let config = Config::parse("targets.daily = 8").unwrap();
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 2, 13).unwrap();
+        let from = chrono::NaiveDate::from_ymd_opt(2024, 2, 12).unwrap();
+        let reports = Report::builder(&log)
+            .config(&config)
+            .range(DateRange(from, today))
+            .build();
+        let days = reports
+            .iter()
+            .take_while(|report| report.title.starts_with("2024"))
+            .map(|report| {
+                (
+                    report.title.as_str(),
+                    report.total.unwrap().target.is_some(),
+                )
+            })
+            .collect::<Vec<_>>();
+        // Planned days are reported without a target.
+        assert_eq!(
+            vec![
+                ("2024-02-12", true),
+                ("2024-02-13", true),
+                ("2024-02-15", false)
+            ],
+            days
+        );
+        let balance = reports[1].total.unwrap().target.unwrap().balance;
+        assert_eq!(Some(chrono::TimeDelta::hours(-8)), balance);
+        assert_eq!(
+            chrono::TimeDelta::hours(11),
+            reports.last().unwrap().total.unwrap().raw
+        );
+
+        // A range that ends before it starts, like that of a future file up to today, is ignored.
+        let reports = Report::builder(&log)
+            .config(&config)
+            .range(DateRange(today, from))
+            .build();
+        assert_eq!("2024-02-15", reports[3].title);
+    }
+
+    #[test]
+    fn deductions() {
+        let log = Log::parse("## 2024-02-13\n- 9-17 ABC\n", &Context::default()).unwrap();
+        let config = Config::parse(indoc::indoc! {r#"
+            [targets]
+            weekly = 40
+
+            [breaks]
+            deductions = [{ after = 6, minutes = 30 }]
+        "#})
+        .unwrap();
+        let reports = Report::builder(&log).config(&config).build();
+        let half = chrono::TimeDelta::minutes(30);
+        let day = &reports[0];
+        // The deduction isn't an entry, so projects keep their time.
+        assert_eq!(
+            vec![("ABC".to_owned(), chrono::TimeDelta::hours(8))],
+            day.entries
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(|(project, sum)| (project.clone(), sum.raw))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(chrono::TimeDelta::hours(7) + half, day.total.unwrap().raw);
+        let week = reports
+            .iter()
+            .find(|report| report.title == "By week")
+            .unwrap();
+        assert_eq!(
+            chrono::TimeDelta::hours(7) + half,
+            week.entries.as_ref().unwrap()[0].1.raw
+        );
+        let by_project = reports
+            .iter()
+            .find(|report| report.title == "By project")
+            .unwrap();
+        assert_eq!(
+            chrono::TimeDelta::hours(8),
+            by_project.entries.as_ref().unwrap()[0].1.raw
+        );
+        assert_eq!(
+            chrono::TimeDelta::hours(7) + half,
+            reports.last().unwrap().total.unwrap().raw
+        );
+    }
+
+    #[test]
+    fn build() {
+        let log = Log::parse(
+            indoc::indoc! {"
+                ## 2024-02-13
+                - 9-12 ABC
+                - 12-13 LUNCH
+                - 13-17 DEF
+            "},
+            &Context::default(),
+        )
+        .unwrap();
+        let titles = |reports: Vec<Report>| {
+            reports
+                .into_iter()
+                .map(|report| report.title)
+                .collect::<Vec<_>>()
+        };
+        let reports = Report::builder(&log).build();
+        assert_eq!(vec!["2024-02-13", "By project", "Total"], titles(reports));
+
+        let config = Config::parse(indoc::indoc! {r#"
+            [targets]
+            weekly = 40
+
+            [breaks]
+            projects = ["LUNCH"]
+        "#})
+        .unwrap();
+        let reports = Report::builder(&log).config(&config).days(false).build();
+        assert_eq!(
+            chrono::TimeDelta::hours(7),
+            reports.last().unwrap().total.unwrap().raw
+        );
+        assert_eq!(
+            vec!["By week", "By project", "Breaks", "Working hours", "Total"],
+            titles(reports)
+        );
+    }
+}
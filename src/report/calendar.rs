@@ -0,0 +1,209 @@
+use super::Report;
+use crate::timelog::{Log, Time};
+use serde::{Deserialize, Deserializer};
+use std::{collections::BTreeMap, fmt, path::PathBuf};
+
+/// Which days are working days, from the `[calendar]` table in the configuration.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Calendar {
+    /// Weekdays that are working days, Monday to Friday by default.
+    #[serde(deserialize_with = "weekdays")]
+    pub workdays: Vec<chrono::Weekday>,
+    /// A file with a public holiday per line, like `2024-12-25 Christmas Day`.
+    #[serde(rename = "holidays")]
+    pub holidays_file: Option<PathBuf>,
+    /// Public holidays by date, as read from the holidays file.
+    #[serde(skip)]
+    pub holidays: BTreeMap<chrono::NaiveDate, String>,
+    /// Days off by date, with the reason, as marked in logs.
+    #[serde(skip)]
+    pub leave: BTreeMap<chrono::NaiveDate, String>,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self {
+            workdays: vec![
+                chrono::Weekday::Mon,
+                chrono::Weekday::Tue,
+                chrono::Weekday::Wed,
+                chrono::Weekday::Thu,
+                chrono::Weekday::Fri,
+            ],
+            holidays_file: None,
+            holidays: BTreeMap::new(),
+            leave: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Day {
+    Workday,
+    Weekend,
+    Holiday(String),
+    Leave(String),
+}
+
+impl fmt::Display for Day {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Workday => write!(f, "workday"),
+            Self::Weekend => write!(f, "weekend"),
+            Self::Holiday(name) | Self::Leave(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl Calendar {
+    pub fn day(&self, date: &chrono::NaiveDate) -> Day {
+        use chrono::Datelike;
+        if let Some(name) = self.holidays.get(date) {
+            Day::Holiday(name.clone())
+        } else if let Some(reason) = self.leave.get(date) {
+            Day::Leave(reason.clone())
+        } else if self.workdays.contains(&date.weekday()) {
+            Day::Workday
+        } else {
+            Day::Weekend
+        }
+    }
+
+    pub fn is_workday(&self, date: &chrono::NaiveDate) -> bool {
+        self.day(date) == Day::Workday
+    }
+
+    /// Adds the days marked as leave in a log, with all-day entries like `- all day: LEAVE` or
+    /// headings like `## 2024-02-14 (vacation)`.
+    pub fn add_leave(&mut self, log: &Log) {
+        for entry in log.0.iter().filter(|entry| entry.time == Time::AllDay) {
+            self.leave.insert(entry.date, entry.project.clone());
+        }
+    }
+
+    /// Leaves out weekend days without time spent, and adds what kind of day it is to the title
+    /// of other days that aren't working days.
+    pub fn annotate(&self, days: &mut Vec<(chrono::NaiveDate, Report)>) {
+        days.retain(|(date, report)| {
+            self.day(date) != Day::Weekend
+                || report
+                    .total
+                    .is_some_and(|total| total.raw > chrono::TimeDelta::zero())
+        });
+        for (date, report) in days.iter_mut() {
+            let day = self.day(date);
+            if day != Day::Workday {
+                report.title = format!("{} ({})", report.title, day);
+            }
+        }
+    }
+
+    /// Parses a list of public holidays, with a date and a name per line. Empty lines and lines
+    /// starting with `#` are skipped.
+    pub fn parse_holidays(
+        input: &str,
+    ) -> Result<BTreeMap<chrono::NaiveDate, String>, (usize, String)> {
+        let mut holidays = BTreeMap::new();
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (date, name) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| (index + 1, format!("invalid date {}", date)))?;
+            let name = match name.trim() {
+                "" => "holiday",
+                name => name,
+            };
+            holidays.insert(date, name.to_owned());
+        }
+        Ok(holidays)
+    }
+}
+
+fn weekdays<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<chrono::Weekday>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|day| {
+            day.parse()
+                .map_err(|_| serde::de::Error::custom(format!("invalid weekday {}", day)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timelog::Context;
+
+    fn date(y: i32, m: u32, d: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn days() {
+        let mut calendar = Calendar {
+            holidays: BTreeMap::from([(date(2024, 2, 13), "Carnival".to_owned())]),
+            ..Calendar::default()
+        };
+        let log = Log::parse(
+            "## 2024-02-14\n- all day: LEAVE\n## 2024-02-15\n- 9-17 ABC\n",
+            &Context::default(),
+        )
+        .unwrap();
+        calendar.add_leave(&log);
+        assert_eq!(Day::Workday, calendar.day(&date(2024, 2, 12)));
+        assert_eq!(
+            Day::Holiday("Carnival".to_owned()),
+            calendar.day(&date(2024, 2, 13))
+        );
+        assert_eq!(
+            Day::Leave("LEAVE".to_owned()),
+            calendar.day(&date(2024, 2, 14))
+        );
+        assert_eq!(Day::Workday, calendar.day(&date(2024, 2, 15)));
+        assert_eq!(Day::Weekend, calendar.day(&date(2024, 2, 17)));
+    }
+
+    #[test]
+    fn annotate() {
+        let log = Log::parse(
+            "## 2024-02-16\n- 9-17 ABC\n## 2024-02-18\n- 9-10 ABC\n## 2024-02-19 (sick)\n",
+            &Context::default(),
+        )
+        .unwrap();
+        let mut calendar = Calendar::default();
+        calendar.add_leave(&log);
+        let mut days = Report::days(&log, crate::report::Fill::Padded, None);
+        calendar.annotate(&mut days);
+        let titles = days
+            .iter()
+            .map(|(_, report)| report.title.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec!["2024-02-16", "2024-02-18 (weekend)", "2024-02-19 (sick)"],
+            titles
+        );
+    }
+
+    #[test]
+    fn parse_holidays() {
+        let input = indoc::indoc! {"
+            # Public holidays
+            2024-12-25 Christmas Day
+
+            2024-12-26
+        "};
+        let expected = BTreeMap::from([
+            (date(2024, 12, 25), "Christmas Day".to_owned()),
+            (date(2024, 12, 26), "holiday".to_owned()),
+        ]);
+        assert_eq!(Ok(expected), Calendar::parse_holidays(input));
+        assert_eq!(
+            Err((1, "invalid date 25-12-2024".to_owned())),
+            Calendar::parse_holidays("25-12-2024 Christmas Day")
+        );
+    }
+}
@@ -0,0 +1,446 @@
+use super::render::{escape_html, escape_markdown, STYLE};
+use super::{Rounding, Sum};
+use crate::timelog::{Log, Time};
+use crate::utils::DateRange;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::Deserialize;
+use std::{collections::BTreeMap, error, fmt, fmt::Write};
+
+/// Billing details for a client, from a `[clients.<name>]` table in the configuration.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Client {
+    /// The name to put on invoices, instead of the key in the configuration.
+    pub name: Option<String>,
+    pub currency: Option<String>,
+    /// The hourly rate for projects without a rate of their own.
+    pub rate: Option<Decimal>,
+    /// Projects billed to this client. Without projects or rates, all projects are.
+    pub projects: Vec<String>,
+    /// Hourly rates per project, for projects billed to this client.
+    pub rates: BTreeMap<String, Decimal>,
+    /// How billed time is rounded for this client, instead of the general rounding policy.
+    pub rounding: Option<Rounding>,
+}
+
+impl Client {
+    pub fn includes(&self, project: &str) -> bool {
+        (self.projects.is_empty() && self.rates.is_empty())
+            || self.projects.iter().any(|p| p == project)
+            || self.rates.contains_key(project)
+    }
+
+    pub fn rate(&self, project: &str) -> Option<Decimal> {
+        self.rates.get(project).copied().or(self.rate)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvoiceError {
+    pub project: String,
+}
+
+impl error::Error for InvoiceError {}
+impl fmt::Display for InvoiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no hourly rate for project {}", self.project)
+    }
+}
+
+/// An invoice line for the time spent on a project.
+#[derive(Debug, PartialEq)]
+pub struct Line {
+    pub project: String,
+    /// The number of days with time spent on the project.
+    pub days: usize,
+    /// The time billed, after rounding.
+    pub duration: chrono::TimeDelta,
+    /// The time spent, before rounding.
+    pub raw: chrono::TimeDelta,
+    pub rate: Decimal,
+    /// The duration times the rate, rounded to cents.
+    pub amount: Decimal,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Invoice {
+    pub client: String,
+    pub currency: Option<String>,
+    pub range: DateRange,
+    pub lines: Vec<Line>,
+    /// The number of days with time spent on any of the projects.
+    pub days: usize,
+}
+
+impl Invoice {
+    /// Itemises the time spent on the client's projects within a range, with a line per project.
+    /// Days off aren't billed. The client's own rounding policy takes precedence over the given
+    /// one.
+    pub fn new(
+        name: &str,
+        client: &Client,
+        log: &Log,
+        range: &DateRange,
+        rounding: Option<&Rounding>,
+    ) -> Result<Self, InvoiceError> {
+        let rounding = client.rounding.as_ref().or(rounding);
+        let Log(entries) = log.within(range);
+        let log = Log(entries
+            .into_iter()
+            .filter(|entry| entry.time != Time::AllDay && client.includes(&entry.project))
+            .collect());
+        let lines = log
+            .by_project()
+            .into_iter()
+            .map(|(project, log)| {
+                let rate = client.rate(&project).ok_or_else(|| InvoiceError {
+                    project: project.clone(),
+                })?;
+                let sum = Sum::of(&log, rounding);
+                let duration = sum.billed();
+                let amount = (Decimal::from(duration.num_minutes()) * rate / Decimal::from(60))
+                    .round_dp_with_strategy(2, ROUNDING);
+                Ok(Line {
+                    days: log.by_date().len(),
+                    project,
+                    duration,
+                    raw: sum.raw,
+                    rate,
+                    amount,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            client: client.name.clone().unwrap_or_else(|| name.to_owned()),
+            currency: client.currency.clone(),
+            range: range.clone(),
+            lines,
+            days: log.by_date().len(),
+        })
+    }
+
+    pub fn duration(&self) -> chrono::TimeDelta {
+        self.lines.iter().map(|line| line.duration).sum()
+    }
+
+    pub fn raw(&self) -> chrono::TimeDelta {
+        self.lines.iter().map(|line| line.raw).sum()
+    }
+
+    /// The sum of the line amounts, which are rounded separately.
+    pub fn total(&self) -> Decimal {
+        self.lines.iter().map(|line| line.amount).sum()
+    }
+
+    pub fn markdown(&self) -> String {
+        let mut output = String::new();
+        writeln!(output, "# Invoice: {}\n", escape_markdown(&self.client)).unwrap();
+        writeln!(output, "Period: {} to {}\n", self.range.0, self.range.1).unwrap();
+        writeln!(
+            output,
+            "| Project | Days | Hours | Rate | Amount{} |",
+            self.currency_suffix()
+        )
+        .unwrap();
+        writeln!(output, "| :-- | --: | --: | --: | --: |").unwrap();
+        for line in self.lines.iter() {
+            writeln!(
+                output,
+                "| {} | {} | {} | {} | {} |",
+                escape_markdown(&line.project),
+                line.days,
+                format_hours(&line.duration),
+                format_amount(line.rate),
+                format_amount(line.amount)
+            )
+            .unwrap();
+        }
+        writeln!(
+            output,
+            "| **Total** | {} | {} | | **{}** |",
+            self.days,
+            format_hours(&self.duration()),
+            format_amount(self.total())
+        )
+        .unwrap();
+        output
+    }
+
+    pub fn html(&self) -> String {
+        let client = escape_html(&self.client);
+        let mut output = String::new();
+        writeln!(output, "<!DOCTYPE html>").unwrap();
+        writeln!(output, "<html>").unwrap();
+        writeln!(output, "<head>").unwrap();
+        writeln!(output, "<meta charset=\"utf-8\">").unwrap();
+        writeln!(output, "<title>Invoice: {}</title>", client).unwrap();
+        writeln!(output, "<style>\n{}</style>", STYLE).unwrap();
+        writeln!(output, "</head>").unwrap();
+        writeln!(output, "<body>").unwrap();
+        writeln!(output, "<h1>Invoice: {}</h1>", client).unwrap();
+        writeln!(
+            output,
+            "<p>Period: {} to {}</p>",
+            self.range.0, self.range.1
+        )
+        .unwrap();
+        writeln!(output, "<table>").unwrap();
+        writeln!(
+            output,
+            "<thead><tr><th>Project</th><th>Days</th><th>Hours</th><th>Rate</th><th>Amount{}</th></tr></thead>",
+            escape_html(&self.currency_suffix())
+        )
+        .unwrap();
+        writeln!(output, "<tbody>").unwrap();
+        for line in self.lines.iter() {
+            writeln!(
+                output,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_html(&line.project),
+                line.days,
+                format_hours(&line.duration),
+                format_amount(line.rate),
+                format_amount(line.amount)
+            )
+            .unwrap();
+        }
+        writeln!(output, "</tbody>").unwrap();
+        writeln!(
+            output,
+            "<tfoot><tr><th>Total</th><th>{}</th><th>{}</th><th></th><th>{}</th></tr></tfoot>",
+            self.days,
+            format_hours(&self.duration()),
+            format_amount(self.total())
+        )
+        .unwrap();
+        writeln!(output, "</table>").unwrap();
+        writeln!(output, "</body>").unwrap();
+        writeln!(output, "</html>").unwrap();
+        output
+    }
+
+    /// Amounts are strings in the JSON output, to keep them exact.
+    pub fn json(&self) -> String {
+        let lines = self
+            .lines
+            .iter()
+            .map(|line| {
+                serde_json::json!({
+                    "project": line.project,
+                    "days": line.days,
+                    "hours": format_hours(&line.duration),
+                    "raw_hours": format_hours(&line.raw),
+                    "rate": format_amount(line.rate),
+                    "amount": format_amount(line.amount),
+                })
+            })
+            .collect::<Vec<_>>();
+        let invoice = serde_json::json!({
+            "client": self.client,
+            "currency": self.currency,
+            "from": self.range.0.to_string(),
+            "until": self.range.1.to_string(),
+            "lines": lines,
+            "days": self.days,
+            "hours": format_hours(&self.duration()),
+            "raw_hours": format_hours(&self.raw()),
+            "total": format_amount(self.total()),
+        });
+        format!("{:#}", invoice)
+    }
+
+    fn currency_suffix(&self) -> String {
+        self.currency
+            .as_ref()
+            .map(|currency| format!(" ({})", currency))
+            .unwrap_or_default()
+    }
+}
+
+const ROUNDING: RoundingStrategy = RoundingStrategy::MidpointAwayFromZero;
+
+fn hours(duration: &chrono::TimeDelta) -> Decimal {
+    Decimal::from(duration.num_minutes()) / Decimal::from(60)
+}
+
+fn format_hours(duration: &chrono::TimeDelta) -> String {
+    format!("{:.2}", hours(duration).round_dp_with_strategy(2, ROUNDING))
+}
+
+fn format_amount(amount: Decimal) -> String {
+    format!("{:.2}", amount.round_dp_with_strategy(2, ROUNDING))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::rounding::{Mode, Scope};
+    use crate::timelog::Context;
+    use std::str::FromStr;
+
+    fn parse(input: &str) -> Log {
+        Log::parse(input, &Context::default()).unwrap()
+    }
+
+    fn decimal(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn range(s: &str) -> DateRange {
+        s.parse().unwrap()
+    }
+
+    fn client() -> Client {
+        Client {
+            name: Some("ACME Corporation".to_owned()),
+            currency: Some("EUR".to_owned()),
+            rate: Some(decimal("90")),
+            projects: vec!["ABC".to_owned()],
+            rates: BTreeMap::from([("DEF".to_owned(), decimal("100.50"))]),
+            rounding: None,
+        }
+    }
+
+    static LOG: &str = indoc::indoc! {"
+        ## 2024-02-13
+        - 9-10:20 ABC
+        - 10:20-11 DEF
+        - 11-12 GHI
+        ## 2024-02-14
+        - 9-9:20 ABC
+        ## 2024-03-01
+        - 9-10 ABC
+    "};
+
+    #[test]
+    fn lines_per_project() {
+        let invoice =
+            Invoice::new("acme", &client(), &parse(LOG), &range("2024-02"), None).unwrap();
+        assert_eq!("ACME Corporation", invoice.client);
+        assert_eq!(
+            vec![
+                Line {
+                    project: "ABC".to_owned(),
+                    days: 2,
+                    duration: chrono::TimeDelta::minutes(100),
+                    raw: chrono::TimeDelta::minutes(100),
+                    rate: decimal("90"),
+                    amount: decimal("150.00"),
+                },
+                Line {
+                    project: "DEF".to_owned(),
+                    days: 1,
+                    duration: chrono::TimeDelta::minutes(40),
+                    raw: chrono::TimeDelta::minutes(40),
+                    rate: decimal("100.50"),
+                    amount: decimal("67.00"),
+                },
+            ],
+            invoice.lines
+        );
+        assert_eq!(2, invoice.days);
+        assert_eq!(decimal("217.00"), invoice.total());
+    }
+
+    #[test]
+    fn days_off() {
+        let log = parse("## 2024-02-13\n- 9-10 ABC\n## 2024-02-14 (vacation)\n");
+        // With a list of projects, with a default rate for all projects, and with only rates.
+        let all = Client {
+            rate: Some(decimal("90")),
+            ..Client::default()
+        };
+        let rates = Client {
+            rates: BTreeMap::from([("ABC".to_owned(), decimal("100"))]),
+            ..Client::default()
+        };
+        for client in [client(), all, rates] {
+            let invoice = Invoice::new("acme", &client, &log, &range("2024-02"), None).unwrap();
+            assert_eq!(
+                vec!["ABC"],
+                invoice
+                    .lines
+                    .iter()
+                    .map(|line| &line.project)
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(1, invoice.days);
+        }
+    }
+
+    #[test]
+    fn exact_amounts() {
+        // 20 minutes at 100.10 is 33.3666..., which floats can't hold.
+        let client = Client {
+            rate: Some(decimal("100.10")),
+            ..Client::default()
+        };
+        let log = parse("## 2024-02-13\n- 9-9:20 ABC\n- 10-10:20 DEF\n- 11-11:20 GHI\n");
+        let invoice = Invoice::new("acme", &client, &log, &range("2024-02"), None).unwrap();
+        assert!(invoice
+            .lines
+            .iter()
+            .all(|line| line.amount == decimal("33.37")));
+        assert_eq!(decimal("100.11"), invoice.total());
+    }
+
+    #[test]
+    fn rounded_lines() {
+        let rounding = Rounding {
+            increment: 30,
+            mode: Mode::Up,
+            scope: Scope::Project,
+        };
+        let log = parse(LOG);
+        let invoice = Invoice::new("acme", &client(), &log, &range("2024-02"), Some(&rounding));
+        let invoice = invoice.unwrap();
+        assert_eq!(chrono::TimeDelta::minutes(120), invoice.lines[0].duration);
+        assert_eq!(chrono::TimeDelta::minutes(100), invoice.lines[0].raw);
+        assert_eq!(decimal("180.00"), invoice.lines[0].amount);
+        assert_eq!(decimal("280.50"), invoice.total());
+    }
+
+    #[test]
+    fn missing_rate() {
+        let client = Client {
+            projects: vec!["ABC".to_owned()],
+            ..Client::default()
+        };
+        let result = Invoice::new("acme", &client, &parse(LOG), &range("2024-02"), None);
+        assert_eq!(
+            Err(InvoiceError {
+                project: "ABC".to_owned()
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn markdown() {
+        let invoice =
+            Invoice::new("acme", &client(), &parse(LOG), &range("2024-02"), None).unwrap();
+        let expected = indoc::indoc! {"
+            # Invoice: ACME Corporation
+
+            Period: 2024-02-01 to 2024-02-29
+
+            | Project | Days | Hours | Rate | Amount (EUR) |
+            | :-- | --: | --: | --: | --: |
+            | ABC | 2 | 1.67 | 90.00 | 150.00 |
+            | DEF | 1 | 0.67 | 100.50 | 67.00 |
+            | **Total** | 2 | 2.33 | | **217.00** |
+        "};
+        assert_eq!(expected, invoice.markdown());
+    }
+
+    #[test]
+    fn json() {
+        let invoice =
+            Invoice::new("acme", &client(), &parse(LOG), &range("2024-02"), None).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&invoice.json()).unwrap();
+        assert_eq!("EUR", value["currency"]);
+        assert_eq!("2024-02-29", value["until"]);
+        assert_eq!("150.00", value["lines"][0]["amount"]);
+        assert_eq!("217.00", value["total"]);
+    }
+}
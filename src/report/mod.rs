@@ -0,0 +1,686 @@
+mod breaks;
+mod builder;
+mod calendar;
+pub mod invoice;
+mod render;
+pub mod rounding;
+pub mod stats;
+mod targets;
+mod timeline;
+
+pub use breaks::Breaks;
+pub use builder::ReportBuilder;
+pub use calendar::Calendar;
+pub use render::{Html, Markdown, Render, Text};
+pub use rounding::Rounding;
+pub use targets::{Budget, Target, Targets};
+pub use timeline::Timeline;
+
+use crate::timelog;
+use crate::utils::{self, DateRange};
+use indexmap::map::IndexMap;
+
+#[derive(Clone)]
+pub enum Fill {
+    /// Adds empty days between the first and last date with entries.
+    Padded,
+    #[allow(dead_code)]
+    Sparse,
+    /// Adds empty days for all dates in a range. Dates outside of it, like planned days after
+    /// today, are kept if they have entries, but don't count towards targets.
+    Within(DateRange),
+}
+
+impl Fill {
+    fn pad<T: Default>(&self, map: &mut IndexMap<chrono::NaiveDate, T>) {
+        match self {
+            Self::Padded => utils::pad_dates(map, None),
+            Self::Sparse => {}
+            Self::Within(range) => {
+                for date in range.clone() {
+                    map.entry(date).or_default();
+                }
+                map.sort_keys();
+            }
+        }
+    }
+
+    /// Whether a date counts towards targets.
+    fn counts(&self, date: &chrono::NaiveDate) -> bool {
+        match self {
+            Self::Within(range) => range.contains(date),
+            Self::Padded | Self::Sparse => true,
+        }
+    }
+}
+
+/// The time spent, and the time billed after rounding if there is a rounding policy.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sum {
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "raw_minutes", with = "utils::minutes")
+    )]
+    pub raw: chrono::TimeDelta,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "rounded_minutes",
+            with = "utils::minutes::option",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub rounded: Option<chrono::TimeDelta>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub target: Option<Target>,
+}
+
+impl Sum {
+    pub(crate) fn of(log: &timelog::Log, rounding: Option<&Rounding>) -> Self {
+        Self {
+            raw: log.sum_duration(),
+            rounded: rounding.map(|rounding| rounding.sum(log)),
+            target: None,
+        }
+    }
+
+    /// Takes time off the raw and rounded sums, like breaks that weren't logged.
+    pub(crate) fn deduct(&mut self, duration: chrono::TimeDelta) {
+        self.raw -= duration;
+        if let Some(rounded) = self.rounded.as_mut() {
+            *rounded -= duration;
+        }
+    }
+
+    /// The rounded sum, or the raw one without a rounding policy.
+    pub fn billed(&self) -> chrono::TimeDelta {
+        self.rounded.unwrap_or(self.raw)
+    }
+}
+
+impl From<chrono::TimeDelta> for Sum {
+    fn from(raw: chrono::TimeDelta) -> Self {
+        Self {
+            raw,
+            rounded: None,
+            target: None,
+        }
+    }
+}
+
+/// A titled table of sums, with an optional total. Reports without entries are just a total.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Report {
+    pub title: String,
+    pub entries: Option<Vec<(String, Sum)>>,
+    pub total: Option<Sum>,
+}
+
+impl Report {
+    /// An empty report, to add entries and a total to.
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            entries: None,
+            total: None,
+        }
+    }
+
+    /// The reports `myw report` prints for a log.
+    pub fn builder(log: &timelog::Log) -> ReportBuilder<'_> {
+        ReportBuilder::new(log)
+    }
+
+    pub fn with_title(self, title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            ..self
+        }
+    }
+
+    pub fn with_entry(mut self, name: impl Into<String>, sum: impl Into<Sum>) -> Self {
+        self.entries
+            .get_or_insert_with(Vec::new)
+            .push((name.into(), sum.into()));
+        self
+    }
+
+    pub fn with_total(self, total: impl Into<Sum>) -> Self {
+        Self {
+            total: Some(total.into()),
+            ..self
+        }
+    }
+
+    #[cfg(test)]
+    pub fn by_date(log: &timelog::Log, fill: Fill) -> Self {
+        let mut logs = log.by_date();
+        fill.pad(&mut logs);
+        Self {
+            title: "By date".to_owned(),
+            entries: Some(
+                logs.drain(..)
+                    .map(|(date, log)| (date.to_string(), log.sum_duration().into()))
+                    .collect::<Vec<_>>(),
+            ),
+            total: None,
+        }
+    }
+
+    pub(crate) fn by_project(log: &timelog::Log, rounding: Option<&Rounding>) -> Self {
+        Self {
+            title: "By project".to_owned(),
+            entries: Some(
+                projects(log)
+                    .map(|(project, log)| (project, Sum::of(&log, rounding)))
+                    .collect::<Vec<_>>(),
+            ),
+            total: None,
+        }
+    }
+
+    pub(crate) fn total(log: &timelog::Log, rounding: Option<&Rounding>) -> Self {
+        Self {
+            title: "Total".to_owned(),
+            entries: None,
+            total: Some(Sum::of(log, rounding)),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn by_date_by_project(
+        log: &timelog::Log,
+        fill: Fill,
+        rounding: Option<&Rounding>,
+    ) -> Vec<Self> {
+        Self::days(log, fill, rounding)
+            .into_iter()
+            .map(|(_, report)| report)
+            .collect()
+    }
+
+    /// Like `by_date_by_project`, with the date of each report.
+    pub(crate) fn days(
+        log: &timelog::Log,
+        fill: Fill,
+        rounding: Option<&Rounding>,
+    ) -> Vec<(chrono::NaiveDate, Self)> {
+        let mut logs = log.by_date();
+        fill.pad(&mut logs);
+        logs.drain(..)
+            .map(|(date, log)| {
+                let total = Sum::of(&log, rounding);
+                let report = Self {
+                    title: date.to_string(),
+                    entries: Some(
+                        projects(&log)
+                            .map(|(project, log)| (project, Sum::of(&log, rounding)))
+                            .collect::<Vec<_>>(),
+                    ),
+                    total: Some(total),
+                };
+                (date, report)
+            })
+            .collect::<Vec<_>>()
+    }
+}
+
+/// The log per project, leaving out projects that only mark days off.
+fn projects(log: &timelog::Log) -> impl Iterator<Item = (String, timelog::Log)> {
+    log.by_project().into_iter().filter(|(_, log)| {
+        log.0
+            .iter()
+            .any(|entry| entry.time != timelog::Time::AllDay)
+    })
+}
+
+/// Formats the billed hours, followed by the raw hours if they were rounded, and how they
+/// compare to the target if there is one.
+fn format_sum(sum: &Sum) -> String {
+    let mut output = match sum.rounded {
+        Some(rounded) => format!(
+            "{} (raw {})",
+            format_hours(&rounded),
+            format_hours(&sum.raw)
+        ),
+        None => format_hours(&sum.raw),
+    };
+    if let Some(target) = &sum.target {
+        let difference = sum.billed() - target.hours;
+        output.push_str(&format!(" of {}", format_hours(&target.hours)));
+        match target.balance {
+            Some(balance) => output.push_str(&format!(
+                " ({}, balance {})",
+                format_signed_hours(&difference),
+                format_signed_hours(&balance)
+            )),
+            None if difference > chrono::TimeDelta::zero() => {
+                output.push_str(&format!(" ({} over)", format_hours(&difference)))
+            }
+            None => output.push_str(&format!(" ({} left)", format_hours(&-difference))),
+        }
+    }
+    output
+}
+
+fn format_signed_hours(td: &chrono::TimeDelta) -> String {
+    match *td > chrono::TimeDelta::zero() {
+        true => format!("+{}", format_hours(td)),
+        false => format_hours(td),
+    }
+}
+
+fn format_hours(td: &chrono::TimeDelta) -> String {
+    format!(
+        "{}",
+        ((td.num_minutes() as f32) / 60.0 * 100.0).round() / 100.0
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rounding::{Mode, Scope};
+
+    fn date(y: i32, m: u32, d: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn parse(input: &str) -> timelog::Log {
+        timelog::Log::parse(input, &timelog::Context::default()).unwrap()
+    }
+
+    #[test]
+    fn by_date_empty() {
+        let log = timelog::Log(vec![]);
+        let report = Report::by_date(&log, Fill::Sparse);
+        assert_eq!(
+            Report {
+                title: "By date".to_owned(),
+                entries: Some(vec![]),
+                total: None
+            },
+            report
+        );
+        let report = Report::by_date(&log, Fill::Padded);
+        assert_eq!(
+            Report {
+                title: "By date".to_owned(),
+                entries: Some(vec![]),
+                total: None
+            },
+            report
+        );
+    }
+
+    #[test]
+    fn by_date_one_date() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13
+            * 9-10 ABC
+            * 10-11 DEF
+            * 11-12 ABC
+        "});
+        let report = Report::by_date(&log, Fill::Sparse);
+        assert_eq!(
+            Report {
+                title: "By date".to_owned(),
+                entries: Some(vec![(
+                    date(2024, 2, 13).to_string(),
+                    chrono::TimeDelta::hours(3).into()
+                )]),
+                total: None
+            },
+            report
+        );
+    }
+
+    #[test]
+    fn by_date_multiple_dates() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13
+            * 9-10 ABC
+            * 10-11 DEF
+            * 11-12 ABC
+            ## 2024-02-14
+            * 9-10 ABC
+            * 10-11 DEF
+        "});
+        let report = Report::by_date(&log, Fill::Sparse);
+        assert_eq!(
+            Report {
+                title: "By date".to_owned(),
+                entries: Some(vec![
+                    (
+                        date(2024, 2, 13).to_string(),
+                        chrono::TimeDelta::hours(3).into()
+                    ),
+                    (
+                        date(2024, 2, 14).to_string(),
+                        chrono::TimeDelta::hours(2).into()
+                    ),
+                ]),
+                total: None
+            },
+            report
+        );
+    }
+
+    #[test]
+    fn by_date_multiple_dates_sparse() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13
+            * 9-10 ABC
+            * 10-11 DEF
+            * 11-12 ABC
+            ## 2024-02-17
+            * 9-10 ABC
+            * 10-11 DEF
+        "});
+        let report = Report::by_date(&log, Fill::Sparse);
+        assert_eq!(
+            Report {
+                title: "By date".to_owned(),
+                entries: Some(vec![
+                    (
+                        date(2024, 2, 13).to_string(),
+                        chrono::TimeDelta::hours(3).into()
+                    ),
+                    (
+                        date(2024, 2, 17).to_string(),
+                        chrono::TimeDelta::hours(2).into()
+                    ),
+                ]),
+                total: None
+            },
+            report
+        );
+    }
+
+    #[test]
+    fn by_date_multiple_dates_padded() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13
+            * 9-10 ABC
+            * 10-11 DEF
+            * 11-12 ABC
+            ## 2024-02-17
+            * 9-10 ABC
+            * 10-11 DEF
+        "});
+        let report = Report::by_date(&log, Fill::Padded);
+        assert_eq!(
+            Report {
+                title: "By date".to_owned(),
+                entries: Some(vec![
+                    (
+                        date(2024, 2, 13).to_string(),
+                        chrono::TimeDelta::hours(3).into()
+                    ),
+                    (
+                        date(2024, 2, 14).to_string(),
+                        chrono::TimeDelta::zero().into()
+                    ),
+                    (
+                        date(2024, 2, 15).to_string(),
+                        chrono::TimeDelta::zero().into()
+                    ),
+                    (
+                        date(2024, 2, 16).to_string(),
+                        chrono::TimeDelta::zero().into()
+                    ),
+                    (
+                        date(2024, 2, 17).to_string(),
+                        chrono::TimeDelta::hours(2).into()
+                    ),
+                ]),
+                total: None
+            },
+            report
+        );
+    }
+
+    #[test]
+    fn by_project_empty() {
+        let log = timelog::Log(vec![]);
+        let report = Report::by_project(&log, None);
+        assert_eq!(
+            Report {
+                title: "By project".to_owned(),
+                entries: Some(vec![]),
+                total: None
+            },
+            report
+        );
+        let report = Report::by_project(&log, None);
+        assert_eq!(
+            Report {
+                title: "By project".to_owned(),
+                entries: Some(vec![]),
+                total: None
+            },
+            report
+        );
+    }
+
+    #[test]
+    fn by_project_one_project() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13
+            * 9-10 ABC
+            * 10-11 ABC
+            ## 2024-02-14
+            * 9-10 ABC
+        "});
+        let report = Report::by_project(&log, None);
+        assert_eq!(
+            Report {
+                title: "By project".to_owned(),
+                entries: Some(vec![(
+                    "ABC".to_string(),
+                    chrono::TimeDelta::hours(3).into()
+                )]),
+                total: None
+            },
+            report
+        );
+    }
+
+    #[test]
+    fn by_project_multiple_projects() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13
+            * 9-10 ABC
+            * 10-11 DEF
+            ## 2024-02-14
+            * 9-10 ABC
+        "});
+        let report = Report::by_project(&log, None);
+        assert_eq!(
+            Report {
+                title: "By project".to_owned(),
+                entries: Some(vec![
+                    ("ABC".to_string(), chrono::TimeDelta::hours(2).into()),
+                    ("DEF".to_string(), chrono::TimeDelta::hours(1).into()),
+                ]),
+                total: None
+            },
+            report
+        );
+    }
+
+    #[test]
+    fn total() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13
+            * 9-10 ABC
+            * 10-11 DEF
+            ## 2024-02-14
+            * 9-10 ABC
+        "});
+        let report = Report::total(&log, None);
+        assert_eq!(
+            Report {
+                title: "Total".to_owned(),
+                entries: None,
+                total: Some(chrono::TimeDelta::hours(3).into())
+            },
+            report
+        );
+    }
+
+    #[test]
+    fn total_with_durations() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13
+            * 9-10 ABC
+            * 1h30 DEF
+            * 45m ABC
+            * 13:00 +15m DEF
+        "});
+        let report = Report::total(&log, None);
+        assert_eq!(Some(chrono::TimeDelta::minutes(210).into()), report.total);
+    }
+
+    #[test]
+    fn by_date_by_project_sparse() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13
+            * 9-10 ABC
+            * 10-11 DEF
+            ## 2024-02-16
+            * 9-10 ABC
+        "});
+        let report = Report::by_date_by_project(&log, Fill::Sparse, None);
+        assert_eq!(
+            vec![
+                Report {
+                    title: "2024-02-13".to_owned(),
+                    entries: Some(vec![
+                        ("ABC".to_string(), chrono::TimeDelta::hours(1).into()),
+                        ("DEF".to_string(), chrono::TimeDelta::hours(1).into()),
+                    ]),
+                    total: Some(chrono::TimeDelta::hours(2).into())
+                },
+                Report {
+                    title: "2024-02-16".to_owned(),
+                    entries: Some(vec![(
+                        "ABC".to_string(),
+                        chrono::TimeDelta::hours(1).into()
+                    ),]),
+                    total: Some(chrono::TimeDelta::hours(1).into())
+                }
+            ],
+            report
+        );
+    }
+
+    #[test]
+    fn by_date_by_project_padded() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13
+            * 9-10 ABC
+            * 10-11 DEF
+            ## 2024-02-16
+            * 9-10 ABC
+        "});
+        let report = Report::by_date_by_project(&log, Fill::Padded, None);
+        assert_eq!(
+            vec![
+                Report {
+                    title: "2024-02-13".to_owned(),
+                    entries: Some(vec![
+                        ("ABC".to_string(), chrono::TimeDelta::hours(1).into()),
+                        ("DEF".to_string(), chrono::TimeDelta::hours(1).into()),
+                    ]),
+                    total: Some(chrono::TimeDelta::hours(2).into())
+                },
+                Report {
+                    title: "2024-02-14".to_owned(),
+                    entries: Some(vec![]),
+                    total: Some(chrono::TimeDelta::hours(0).into())
+                },
+                Report {
+                    title: "2024-02-15".to_owned(),
+                    entries: Some(vec![]),
+                    total: Some(chrono::TimeDelta::hours(0).into())
+                },
+                Report {
+                    title: "2024-02-16".to_owned(),
+                    entries: Some(vec![(
+                        "ABC".to_string(),
+                        chrono::TimeDelta::hours(1).into()
+                    ),]),
+                    total: Some(chrono::TimeDelta::hours(1).into())
+                }
+            ],
+            report
+        );
+    }
+
+    #[test]
+    fn by_project_rounded() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13
+            * 9:00-9:10 ABC
+            * 9:10-9:20 ABC
+            * 9:20-9:30 DEF
+        "});
+        let rounding = Rounding {
+            increment: 15,
+            mode: Mode::Up,
+            scope: Scope::Entry,
+        };
+        let report = Report::by_project(&log, Some(&rounding));
+        assert_eq!(
+            Some(vec![
+                (
+                    "ABC".to_string(),
+                    Sum {
+                        raw: chrono::TimeDelta::minutes(20),
+                        rounded: Some(chrono::TimeDelta::minutes(30)),
+                        target: None,
+                    }
+                ),
+                (
+                    "DEF".to_string(),
+                    Sum {
+                        raw: chrono::TimeDelta::minutes(10),
+                        rounded: Some(chrono::TimeDelta::minutes(15)),
+                        target: None,
+                    }
+                ),
+            ]),
+            report.entries
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let target = Target {
+            hours: chrono::TimeDelta::hours(8),
+            balance: Some(chrono::TimeDelta::minutes(-30)),
+        };
+        let report = Report::new("2024-02-13")
+            .with_entry("ABC", chrono::TimeDelta::minutes(20))
+            .with_total(Sum {
+                raw: chrono::TimeDelta::minutes(450),
+                rounded: Some(chrono::TimeDelta::minutes(465)),
+                target: Some(target),
+            });
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(
+            r#"{"title":"2024-02-13","entries":[["ABC",{"raw_minutes":20}]],"total":{"raw_minutes":450,"rounded_minutes":465,"target":{"minutes":480,"balance_minutes":-30}}}"#,
+            json
+        );
+        assert_eq!(report, serde_json::from_str(&json).unwrap());
+    }
+}
@@ -0,0 +1,328 @@
+use super::{format_sum, Report};
+use colored::Colorize;
+use std::fmt::Write;
+
+/// Turns reports into text in some output format.
+pub trait Render {
+    /// Renders a single report.
+    fn report(&self, report: &Report) -> String;
+
+    /// Renders a sequence of reports as one document.
+    fn document(&self, reports: &[Report]) -> String {
+        reports
+            .iter()
+            .map(|report| self.report(report))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Plain text for the terminal, with bold titles.
+pub struct Text;
+
+/// A Markdown table per report, to paste into a log or a pull request.
+pub struct Markdown;
+
+/// A standalone HTML page with a table per report.
+pub struct Html {
+    pub title: String,
+}
+
+impl Render for Text {
+    fn report(&self, report: &Report) -> String {
+        let mut output = String::new();
+        let title = report.title.bold();
+        if let Some(total) = report.total {
+            let total = format_sum(&total);
+            writeln!(output, "{}: {}", title, total).unwrap();
+        } else {
+            writeln!(output, "{}", title).unwrap();
+        }
+        if let Some(entries) = &report.entries {
+            for (name, hours) in entries {
+                let hours = format_sum(hours);
+                writeln!(output, "{}: {}", name, hours).unwrap();
+            }
+        }
+        output
+    }
+}
+
+impl Render for Markdown {
+    fn report(&self, report: &Report) -> String {
+        let mut output = String::new();
+        let total = report.total.as_ref().map(format_sum).unwrap_or_default();
+        let title = escape_markdown(&report.title);
+        writeln!(output, "| {} | {} |", title, total).unwrap();
+        writeln!(output, "| :-- | --: |").unwrap();
+        for (name, hours) in report.entries.iter().flatten() {
+            let hours = format_sum(hours);
+            writeln!(output, "| {} | {} |", escape_markdown(name), hours).unwrap();
+        }
+        output
+    }
+}
+
+impl Render for Html {
+    fn report(&self, report: &Report) -> String {
+        let mut output = String::new();
+        let total = report.total.as_ref().map(format_sum).unwrap_or_default();
+        writeln!(output, "<table>").unwrap();
+        writeln!(
+            output,
+            "<thead><tr><th>{}</th><th>{}</th></tr></thead>",
+            escape_html(&report.title),
+            total
+        )
+        .unwrap();
+        if let Some(entries) = &report.entries {
+            writeln!(output, "<tbody>").unwrap();
+            for (name, hours) in entries {
+                let hours = format_sum(hours);
+                writeln!(
+                    output,
+                    "<tr><td>{}</td><td>{}</td></tr>",
+                    escape_html(name),
+                    hours
+                )
+                .unwrap();
+            }
+            writeln!(output, "</tbody>").unwrap();
+        }
+        writeln!(output, "</table>").unwrap();
+        output
+    }
+
+    fn document(&self, reports: &[Report]) -> String {
+        let tables = reports
+            .iter()
+            .map(|report| self.report(report))
+            .collect::<String>();
+        let title = escape_html(&self.title);
+        let mut output = String::new();
+        writeln!(output, "<!DOCTYPE html>").unwrap();
+        writeln!(output, "<html>").unwrap();
+        writeln!(output, "<head>").unwrap();
+        writeln!(output, "<meta charset=\"utf-8\">").unwrap();
+        writeln!(output, "<title>{}</title>", title).unwrap();
+        writeln!(output, "<style>\n{}</style>", STYLE).unwrap();
+        writeln!(output, "</head>").unwrap();
+        writeln!(output, "<body>").unwrap();
+        writeln!(output, "<h1>{}</h1>", title).unwrap();
+        output.push_str(&tables);
+        writeln!(output, "</body>").unwrap();
+        writeln!(output, "</html>").unwrap();
+        output
+    }
+}
+
+pub(super) static STYLE: &str = "\
+body { font-family: sans-serif; }
+table { border-collapse: collapse; margin-bottom: 1.5em; min-width: 20em; }
+th, td { padding: 0.25em 0.75em; border-bottom: 1px solid #ddd; }
+th:first-child, td:first-child { text-align: left; }
+th + th, td + td { text-align: right; }
+";
+
+pub(super) fn escape_markdown(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+pub(super) fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Sum;
+
+    #[test]
+    fn fmt_as_text_emtpy() {
+        let report = Report {
+            title: "By project".to_owned(),
+            entries: None,
+            total: None,
+        };
+        let result = Text.report(&report);
+        let expected = indoc::indoc! {"
+            \u{1b}[1mBy project\u{1b}[0m
+        "};
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn fmt_as_text_with_entries() {
+        let report = Report {
+            title: "By project".to_owned(),
+            entries: Some(vec![
+                ("ABC".to_string(), chrono::TimeDelta::hours(2).into()),
+                ("DEF".to_string(), chrono::TimeDelta::hours(1).into()),
+            ]),
+            total: None,
+        };
+        let result = Text.report(&report);
+        let expected = indoc::indoc! {"
+            \u{1b}[1mBy project\u{1b}[0m
+            ABC: 2
+            DEF: 1
+        "};
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn fmt_as_text_with_total() {
+        let report = Report {
+            title: "Total".to_owned(),
+            entries: None,
+            total: Some(chrono::TimeDelta::hours(3).into()),
+        };
+        let result = Text.report(&report);
+        let expected = indoc::indoc! {"
+            \u{1b}[1mTotal\u{1b}[0m: 3
+        "};
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn fmt_as_text_with_entries_and_total() {
+        let report = Report {
+            title: "By project".to_owned(),
+            entries: Some(vec![
+                ("ABC".to_string(), chrono::TimeDelta::hours(2).into()),
+                ("DEF".to_string(), chrono::TimeDelta::hours(1).into()),
+            ]),
+            total: Some(chrono::TimeDelta::hours(3).into()),
+        };
+        let result = Text.report(&report);
+        let expected = indoc::indoc! {"
+            \u{1b}[1mBy project\u{1b}[0m: 3
+            ABC: 2
+            DEF: 1
+        "};
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn text_document() {
+        let reports = [
+            Report {
+                title: "2024-02-13".to_owned(),
+                entries: Some(vec![(
+                    "ABC".to_string(),
+                    chrono::TimeDelta::hours(2).into(),
+                )]),
+                total: Some(chrono::TimeDelta::hours(2).into()),
+            },
+            Report {
+                title: "Total".to_owned(),
+                entries: None,
+                total: Some(chrono::TimeDelta::hours(2).into()),
+            },
+        ];
+        let result = Text.document(&reports);
+        let expected = indoc::indoc! {"
+            \u{1b}[1m2024-02-13\u{1b}[0m: 2
+            ABC: 2
+
+            \u{1b}[1mTotal\u{1b}[0m: 2
+        "};
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn markdown_with_entries_and_total() {
+        let report = Report {
+            title: "2024-02-13".to_owned(),
+            entries: Some(vec![
+                ("ABC".to_string(), chrono::TimeDelta::minutes(90).into()),
+                ("A|B".to_string(), chrono::TimeDelta::hours(1).into()),
+            ]),
+            total: Some(chrono::TimeDelta::minutes(150).into()),
+        };
+        let expected = indoc::indoc! {r"
+            | 2024-02-13 | 2.5 |
+            | :-- | --: |
+            | ABC | 1.5 |
+            | A\|B | 1 |
+        "};
+        assert_eq!(expected, Markdown.report(&report));
+    }
+
+    #[test]
+    fn markdown_without_entries() {
+        let report = Report {
+            title: "Total".to_owned(),
+            entries: None,
+            total: Some(chrono::TimeDelta::hours(3).into()),
+        };
+        let expected = indoc::indoc! {"
+            | Total | 3 |
+            | :-- | --: |
+        "};
+        assert_eq!(expected, Markdown.report(&report));
+    }
+
+    #[test]
+    fn html_report() {
+        let report = Report {
+            title: "By project".to_owned(),
+            entries: Some(vec![(
+                "<ABC>".to_string(),
+                chrono::TimeDelta::hours(2).into(),
+            )]),
+            total: None,
+        };
+        let html = Html {
+            title: "Week 7".to_owned(),
+        };
+        let expected = indoc::indoc! {"
+            <table>
+            <thead><tr><th>By project</th><th></th></tr></thead>
+            <tbody>
+            <tr><td>&lt;ABC&gt;</td><td>2</td></tr>
+            </tbody>
+            </table>
+        "};
+        assert_eq!(expected, html.report(&report));
+    }
+
+    #[test]
+    fn html_document() {
+        let report = Report {
+            title: "Total".to_owned(),
+            entries: None,
+            total: Some(chrono::TimeDelta::hours(3).into()),
+        };
+        let html = Html {
+            title: "Week 7 & 8".to_owned(),
+        };
+        let result = html.document(&[report]);
+        assert!(result.starts_with("<!DOCTYPE html>\n"));
+        assert!(result.contains("<title>Week 7 &amp; 8</title>"));
+        assert!(result
+            .contains("<h1>Week 7 &amp; 8</h1>\n<table>\n<thead><tr><th>Total</th><th>3</th>"));
+        assert!(result.ends_with("</table>\n</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn text_rounded() {
+        let report = Report {
+            title: "Total".to_owned(),
+            entries: None,
+            total: Some(Sum {
+                raw: chrono::TimeDelta::minutes(170),
+                rounded: Some(chrono::TimeDelta::hours(3)),
+                target: None,
+            }),
+        };
+        let result = Text.report(&report);
+        let expected = indoc::indoc! {"
+            \u{1b}[1mTotal\u{1b}[0m: 3 (raw 2.83)
+        "};
+        assert_eq!(expected, result);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::timelog::Log;
+use serde::Deserialize;
+
+/// How billed time is rounded, from the `[rounding]` table in the configuration.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Rounding {
+    /// The number of minutes to round to, like 6, 15 or 30.
+    pub increment: u32,
+    #[serde(default)]
+    pub mode: Mode,
+    #[serde(default)]
+    pub scope: Scope,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    Up,
+    Down,
+    #[default]
+    Nearest,
+}
+
+/// What is rounded: each entry, the time per day or the time per project.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    #[default]
+    Entry,
+    Day,
+    Project,
+}
+
+impl Rounding {
+    /// Rounds a duration to the increment. Negative durations, like deducted breaks, are exact and
+    /// left as they are.
+    pub fn round(&self, duration: chrono::TimeDelta) -> chrono::TimeDelta {
+        let increment = i64::from(self.increment) * 60;
+        if increment <= 0 || duration < chrono::TimeDelta::zero() {
+            return duration;
+        }
+        let seconds = duration.num_seconds();
+        let down = seconds - seconds.rem_euclid(increment);
+        let up = if down == seconds {
+            down
+        } else {
+            down + increment
+        };
+        let rounded = match self.mode {
+            Mode::Up => up,
+            Mode::Down => down,
+            Mode::Nearest if seconds - down < up - seconds => down,
+            Mode::Nearest => up,
+        };
+        chrono::TimeDelta::seconds(rounded)
+    }
+
+    /// The total duration of a log, rounded per entry, day or project.
+    pub fn sum(&self, log: &Log) -> chrono::TimeDelta {
+        match self.scope {
+            Scope::Entry => log.0.iter().map(|entry| self.round(entry.duration())).sum(),
+            Scope::Day => log
+                .by_date()
+                .values()
+                .map(|log| self.round(log.sum_duration()))
+                .sum(),
+            Scope::Project => log
+                .by_project()
+                .values()
+                .map(|log| self.round(log.sum_duration()))
+                .sum(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timelog::Context;
+
+    fn rounding(increment: u32, mode: Mode, scope: Scope) -> Rounding {
+        Rounding {
+            increment,
+            mode,
+            scope,
+        }
+    }
+
+    fn minutes(minutes: i64) -> chrono::TimeDelta {
+        chrono::TimeDelta::minutes(minutes)
+    }
+
+    #[test]
+    fn round() {
+        let up = rounding(15, Mode::Up, Scope::Entry);
+        let down = rounding(15, Mode::Down, Scope::Entry);
+        let nearest = rounding(15, Mode::Nearest, Scope::Entry);
+        assert_eq!(minutes(15), up.round(minutes(1)));
+        assert_eq!(minutes(0), down.round(minutes(14)));
+        assert_eq!(minutes(0), nearest.round(minutes(7)));
+        assert_eq!(minutes(15), nearest.round(minutes(8)));
+        assert_eq!(minutes(90), up.round(minutes(90)));
+        assert_eq!(
+            minutes(42),
+            rounding(6, Mode::Up, Scope::Entry).round(minutes(37))
+        );
+    }
+
+    #[test]
+    fn sum_per_scope() {
+        let log = Log::parse(
+            indoc::indoc! {"
+                ## 2024-02-13
+                - 9:00-9:10 ABC
+                - 9:10-9:20 DEF
+                - 9:20-9:30 ABC
+                ## 2024-02-14
+                - 9:00-9:10 ABC
+            "},
+            &Context::default(),
+        )
+        .unwrap();
+        assert_eq!(minutes(60), rounding(15, Mode::Up, Scope::Entry).sum(&log));
+        assert_eq!(minutes(45), rounding(15, Mode::Up, Scope::Day).sum(&log));
+        assert_eq!(
+            minutes(45),
+            rounding(15, Mode::Up, Scope::Project).sum(&log)
+        );
+        assert_eq!(
+            minutes(30),
+            rounding(30, Mode::Nearest, Scope::Project).sum(&log)
+        );
+    }
+}
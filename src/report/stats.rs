@@ -0,0 +1,421 @@
+use super::{format_hours, Calendar};
+use crate::timelog::Log;
+use crate::utils::DateRange;
+use chrono::{Datelike, Timelike};
+use colored::Colorize;
+use indexmap::map::IndexMap;
+use std::fmt::Write;
+
+/// Shades for the weekday heatmap, from no time at all to the most time in any hour.
+static SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// Statistics about the time spent within a range.
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub range: DateRange,
+    pub total: chrono::TimeDelta,
+    /// Working days in the range, according to the calendar.
+    pub workdays: usize,
+    /// Days with time spent.
+    pub days: usize,
+    /// The median time of the first entry of a day, for days with clock times.
+    pub median_start: Option<chrono::NaiveTime>,
+    /// The median time of the last entry of a day, for days with clock times.
+    pub median_end: Option<chrono::NaiveTime>,
+    /// The longest run of working days with time spent, as the first and last day.
+    pub longest_streak: Option<DateRange>,
+    /// Time spent per week or month, with the number of working days in it and the time per
+    /// project.
+    pub periods: Vec<Period>,
+    /// Minutes spent per weekday (Monday first) and hour of the day.
+    pub heatmap: [[i64; 24]; 7],
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Period {
+    pub name: String,
+    pub workdays: usize,
+    pub projects: Vec<(String, chrono::TimeDelta)>,
+}
+
+impl Period {
+    pub fn total(&self) -> chrono::TimeDelta {
+        self.projects.iter().map(|(_, duration)| *duration).sum()
+    }
+}
+
+impl Stats {
+    /// Collects statistics from the entries within a range. Periods are ISO weeks for ranges of
+    /// up to three months, and months otherwise.
+    pub fn new(log: &Log, range: &DateRange, calendar: &Calendar) -> Self {
+        let log = log.within(range);
+        let by_date = log.by_date();
+        let dates = range.clone().collect::<Vec<_>>();
+        let workdays = dates
+            .iter()
+            .filter(|date| calendar.is_workday(date))
+            .count();
+        let worked = |date: &chrono::NaiveDate| {
+            by_date
+                .get(date)
+                .is_some_and(|log| log.sum_duration() > chrono::TimeDelta::zero())
+        };
+
+        let mut starts = vec![];
+        let mut ends = vec![];
+        for log in by_date.values() {
+            if let Some(from) = log.0.iter().filter_map(|entry| entry.from()).min() {
+                starts.push(from.time());
+            }
+            if let Some(until) = log.0.iter().filter_map(|entry| entry.until()).max() {
+                ends.push(until.time());
+            }
+        }
+
+        // Streaks with the number of workdays in them, which only go up by one per workday.
+        let mut longest: Option<(DateRange, usize)> = None;
+        let mut streak: Option<(DateRange, usize)> = None;
+        for date in dates.iter().filter(|date| calendar.is_workday(date)) {
+            streak = match (streak, worked(date)) {
+                (Some((DateRange(from, _), workdays)), true) => {
+                    Some((DateRange(from, *date), workdays + 1))
+                }
+                (None, true) => Some((DateRange::day(*date), 1)),
+                (_, false) => None,
+            };
+            if let Some((current, workdays)) = &streak {
+                if longest.as_ref().map_or(true, |(_, most)| workdays > most) {
+                    longest = Some((current.clone(), *workdays));
+                }
+            }
+        }
+        let longest_streak = longest.map(|(range, _)| range);
+
+        let weekly = range.1 - range.0 <= chrono::TimeDelta::days(92);
+        let mut periods: IndexMap<String, Period> = IndexMap::new();
+        for date in dates.iter() {
+            let name = match weekly {
+                true => {
+                    let week = date.iso_week();
+                    format!("{}-W{:02}", week.year(), week.week())
+                }
+                false => date.format("%Y-%m").to_string(),
+            };
+            let period = periods.entry(name.clone()).or_insert_with(|| Period {
+                name,
+                workdays: 0,
+                projects: vec![],
+            });
+            if calendar.is_workday(date) {
+                period.workdays += 1;
+            }
+            for entry in by_date.get(date).into_iter().flat_map(|log| log.0.iter()) {
+                match period
+                    .projects
+                    .iter_mut()
+                    .find(|(project, _)| project == &entry.project)
+                {
+                    Some((_, duration)) => *duration += entry.duration(),
+                    None => period
+                        .projects
+                        .push((entry.project.clone(), entry.duration())),
+                }
+            }
+        }
+        for period in periods.values_mut() {
+            period
+                .projects
+                .retain(|(_, duration)| *duration > chrono::TimeDelta::zero());
+            period
+                .projects
+                .sort_by_key(|(_, duration)| std::cmp::Reverse(*duration));
+        }
+
+        let mut heatmap = [[0; 24]; 7];
+        for entry in log.0.iter() {
+            let (Some(from), Some(until)) = (entry.from(), entry.until()) else {
+                continue;
+            };
+            let mut time = from;
+            while time < until {
+                let hour_end = time.with_minute(0).unwrap().with_second(0).unwrap()
+                    + chrono::TimeDelta::hours(1);
+                let end = hour_end.min(until);
+                let weekday = time.weekday().num_days_from_monday() as usize;
+                heatmap[weekday][time.hour() as usize] += (end - time).num_minutes();
+                time = end;
+            }
+        }
+
+        Self {
+            range: range.clone(),
+            total: log.sum_duration(),
+            workdays,
+            days: dates.iter().filter(|date| worked(date)).count(),
+            median_start: median(starts),
+            median_end: median(ends),
+            longest_streak,
+            periods: periods.into_values().collect(),
+            heatmap,
+        }
+    }
+
+    /// The average time spent per working day.
+    pub fn average(&self) -> chrono::TimeDelta {
+        average(self.total, self.workdays)
+    }
+
+    pub fn text(&self) -> String {
+        let mut output = String::new();
+        let time = |time: Option<chrono::NaiveTime>| {
+            time.map_or("-".to_owned(), |time| time.format("%H:%M").to_string())
+        };
+        writeln!(output, "{}: {}", "Stats".bold(), self.range).unwrap();
+        writeln!(output, "Total: {}", format_hours(&self.total)).unwrap();
+        writeln!(
+            output,
+            "Days worked: {} of {} workdays",
+            self.days, self.workdays
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "Average per workday: {}",
+            format_hours(&self.average())
+        )
+        .unwrap();
+        writeln!(output, "Median start: {}", time(self.median_start)).unwrap();
+        writeln!(output, "Median end: {}", time(self.median_end)).unwrap();
+        if let Some(DateRange(from, until)) = &self.longest_streak {
+            writeln!(output, "Longest streak: {} to {}", from, until).unwrap();
+        }
+
+        writeln!(output, "\n{}", "Over time".bold()).unwrap();
+        for period in self.periods.iter() {
+            let total = period.total();
+            let shares = period
+                .projects
+                .iter()
+                .map(|(project, duration)| format!("{} {}%", project, percentage(*duration, total)))
+                .collect::<Vec<_>>();
+            write!(
+                output,
+                "{}: {} ({} per workday)",
+                period.name,
+                format_hours(&total),
+                format_hours(&average(total, period.workdays)),
+            )
+            .unwrap();
+            if !shares.is_empty() {
+                write!(output, " {}", shares.join(", ")).unwrap();
+            }
+            output.push('\n');
+        }
+
+        writeln!(output, "\n{}", "By weekday and hour".bold()).unwrap();
+        let max = self.heatmap.iter().flatten().copied().max().unwrap_or(0);
+        writeln!(output, "    0     6     12    18").unwrap();
+        for (weekday, hours) in self.heatmap.iter().enumerate() {
+            let name = chrono::Weekday::try_from(weekday as u8).unwrap();
+            let cells = hours
+                .iter()
+                .map(|minutes| shade(*minutes, max))
+                .collect::<String>();
+            writeln!(output, "{} {}", name, cells).unwrap();
+        }
+        output
+    }
+
+    pub fn json(&self) -> String {
+        let time =
+            |time: Option<chrono::NaiveTime>| time.map(|time| time.format("%H:%M").to_string());
+        let periods = self
+            .periods
+            .iter()
+            .map(|period| {
+                let projects = period
+                    .projects
+                    .iter()
+                    .map(|(project, duration)| {
+                        serde_json::json!({
+                            "project": project,
+                            "hours": format_hours(duration),
+                            "percentage": percentage(*duration, period.total()),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                serde_json::json!({
+                    "name": period.name,
+                    "hours": format_hours(&period.total()),
+                    "workdays": period.workdays,
+                    "projects": projects,
+                })
+            })
+            .collect::<Vec<_>>();
+        let heatmap = self
+            .heatmap
+            .iter()
+            .enumerate()
+            .map(|(weekday, minutes)| {
+                let name = chrono::Weekday::try_from(weekday as u8).unwrap();
+                (name.to_string(), serde_json::json!(minutes))
+            })
+            .collect::<serde_json::Map<_, _>>();
+        let stats = serde_json::json!({
+            "from": self.range.0.to_string(),
+            "until": self.range.1.to_string(),
+            "hours": format_hours(&self.total),
+            "workdays": self.workdays,
+            "days": self.days,
+            "average": format_hours(&self.average()),
+            "median_start": time(self.median_start),
+            "median_end": time(self.median_end),
+            "longest_streak": self.longest_streak.as_ref().map(|DateRange(from, until)| {
+                serde_json::json!({ "from": from.to_string(), "until": until.to_string() })
+            }),
+            "periods": periods,
+            "heatmap": heatmap,
+        });
+        format!("{:#}", stats)
+    }
+}
+
+fn average(total: chrono::TimeDelta, days: usize) -> chrono::TimeDelta {
+    match i32::try_from(days) {
+        Ok(days @ 1..) => total / days,
+        _ => chrono::TimeDelta::zero(),
+    }
+}
+
+fn percentage(part: chrono::TimeDelta, total: chrono::TimeDelta) -> i64 {
+    match total.num_minutes() {
+        0 => 0,
+        total => (part.num_minutes() * 100 + total / 2) / total,
+    }
+}
+
+/// The middle time, or the average of the two middle times.
+fn median(mut times: Vec<chrono::NaiveTime>) -> Option<chrono::NaiveTime> {
+    times.sort();
+    let middle = times.len() / 2;
+    match times.len() {
+        0 => None,
+        n if n % 2 == 1 => Some(times[middle]),
+        _ => {
+            let (a, b) = (times[middle - 1], times[middle]);
+            Some(a + (b - a) / 2)
+        }
+    }
+}
+
+fn shade(minutes: i64, max: i64) -> char {
+    if minutes <= 0 || max <= 0 {
+        return SHADES[0];
+    }
+    let index = (minutes * (SHADES.len() as i64 - 1) + max - 1) / max;
+    SHADES[index as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timelog::Context;
+
+    fn date(y: i32, m: u32, d: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn time(h: u32, m: u32) -> chrono::NaiveTime {
+        chrono::NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    static LOG: &str = indoc::indoc! {"
+        ## 2024-02-12
+        - 9-12 ABC
+        - 13-17 DEF
+        ## 2024-02-13
+        - 8:30-12:30 ABC
+        ## 2024-02-14
+        - 10-18 ABC
+        ## 2024-02-16
+        - 9-11 DEF
+        ## 2024-02-17
+        - 1h GHI
+    "};
+
+    fn stats() -> Stats {
+        let log = Log::parse(LOG, &Context::default()).unwrap();
+        let range = DateRange(date(2024, 2, 12), date(2024, 2, 18));
+        Stats::new(&log, &range, &Calendar::default())
+    }
+
+    #[test]
+    fn summary() {
+        let stats = stats();
+        assert_eq!(chrono::TimeDelta::hours(22), stats.total);
+        assert_eq!(5, stats.workdays);
+        assert_eq!(5, stats.days);
+        assert_eq!(chrono::TimeDelta::minutes(264), stats.average());
+        assert_eq!(Some(time(9, 0)), stats.median_start);
+        assert_eq!(Some(time(14, 45)), stats.median_end);
+        assert_eq!(
+            Some(DateRange(date(2024, 2, 12), date(2024, 2, 14))),
+            stats.longest_streak
+        );
+    }
+
+    #[test]
+    fn periods() {
+        let stats = stats();
+        assert_eq!(
+            vec![Period {
+                name: "2024-W07".to_owned(),
+                workdays: 5,
+                projects: vec![
+                    ("ABC".to_owned(), chrono::TimeDelta::hours(15)),
+                    ("DEF".to_owned(), chrono::TimeDelta::hours(6)),
+                    ("GHI".to_owned(), chrono::TimeDelta::hours(1)),
+                ],
+            }],
+            stats.periods
+        );
+    }
+
+    #[test]
+    fn text_without_shares() {
+        let log = Log::parse(LOG, &Context::default()).unwrap();
+        let range = DateRange(date(2024, 2, 12), date(2024, 2, 25));
+        let text = Stats::new(&log, &range, &Calendar::default()).text();
+        assert!(
+            text.contains("2024-W07: 22 (4.4 per workday) ABC 68%"),
+            "{}",
+            text
+        );
+        assert!(text.contains("\n2024-W08: 0 (0 per workday)\n"), "{}", text);
+    }
+
+    #[test]
+    fn heatmap() {
+        let stats = stats();
+        assert_eq!(60, stats.heatmap[0][9]);
+        assert_eq!(0, stats.heatmap[0][12]);
+        assert_eq!(30, stats.heatmap[1][8]);
+        assert_eq!(30, stats.heatmap[1][12]);
+        assert_eq!(0, stats.heatmap[5].iter().sum::<i64>());
+    }
+
+    #[test]
+    fn median_of_even_count() {
+        assert_eq!(None, median(vec![]));
+        assert_eq!(Some(time(9, 15)), median(vec![time(9, 30), time(9, 0)]));
+    }
+
+    #[test]
+    fn json() {
+        let value: serde_json::Value = serde_json::from_str(&stats().json()).unwrap();
+        assert_eq!("4.4", value["average"]);
+        assert_eq!("09:00", value["median_start"]);
+        assert_eq!(68, value["periods"][0]["projects"][0]["percentage"]);
+        assert_eq!(60, value["heatmap"]["Mon"][9]);
+    }
+}
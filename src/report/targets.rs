@@ -0,0 +1,274 @@
+use super::{Calendar, Fill, Report, Rounding, Sum};
+use crate::timelog::Log;
+use crate::utils::DateRange;
+use chrono::Datelike;
+use indexmap::map::IndexMap;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Hours to work per day and per week, from the `[targets]` table in the configuration.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Targets {
+    pub daily: Option<Decimal>,
+    pub weekly: Option<Decimal>,
+}
+
+impl Targets {
+    pub fn daily(&self) -> Option<chrono::TimeDelta> {
+        self.daily.map(duration)
+    }
+
+    pub fn weekly(&self) -> Option<chrono::TimeDelta> {
+        self.weekly.map(duration)
+    }
+}
+
+/// Hours budgeted for a project over a date range, from a `[budgets.<project>]` table in the
+/// configuration.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Budget {
+    pub hours: Decimal,
+    pub range: DateRange,
+}
+
+/// The hours a sum is compared to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Target {
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "minutes", with = "crate::utils::minutes")
+    )]
+    pub hours: chrono::TimeDelta,
+    /// The overtime so far, including this sum, for targets that carry over from one day or week
+    /// to the next.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "balance_minutes",
+            with = "crate::utils::minutes::option",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub balance: Option<chrono::TimeDelta>,
+}
+
+impl Report {
+    /// Compares the total of each day to the daily target, in order, keeping a running balance of
+    /// overtime. The target is zero on days that aren't working days in the calendar.
+    pub(crate) fn track(
+        days: &mut [(chrono::NaiveDate, Report)],
+        hours: chrono::TimeDelta,
+        calendar: &Calendar,
+    ) {
+        let mut balance = chrono::TimeDelta::zero();
+        for (date, report) in days.iter_mut() {
+            let Some(total) = report.total.as_mut() else {
+                continue;
+            };
+            let hours = match calendar.is_workday(date) {
+                true => hours,
+                false => chrono::TimeDelta::zero(),
+            };
+            balance += total.billed() - hours;
+            total.target = Some(Target {
+                hours,
+                balance: Some(balance),
+            });
+        }
+    }
+
+    /// The time per ISO week, compared to the weekly target if there is one. The target is spread
+    /// over the working days in the calendar, so weeks with holidays or leave, or that are only
+    /// partly filled, have a lower target. Deductions per day, like for breaks that weren't
+    /// logged, are taken off the weeks they're in.
+    pub(crate) fn by_week(
+        log: &Log,
+        fill: Fill,
+        rounding: Option<&Rounding>,
+        target: Option<chrono::TimeDelta>,
+        calendar: &Calendar,
+        deductions: &BTreeMap<chrono::NaiveDate, chrono::TimeDelta>,
+    ) -> Self {
+        let mut logs = log.by_date();
+        fill.pad(&mut logs);
+        let mut weeks: IndexMap<String, (Log, i32, chrono::TimeDelta)> = IndexMap::new();
+        for (date, Log(entries)) in logs {
+            let week = date.iso_week();
+            let name = format!("{}-W{:02}", week.year(), week.week());
+            let (log, workdays, deducted) = weeks.entry(name).or_default();
+            log.0.extend(entries);
+            if calendar.is_workday(&date) && fill.counts(&date) {
+                *workdays += 1;
+            }
+            *deducted += deductions.get(&date).copied().unwrap_or_default();
+        }
+        let mut entries = weeks
+            .into_iter()
+            .map(|(name, (log, workdays, deducted))| {
+                let mut sum = Sum::of(&log, rounding);
+                sum.deduct(deducted);
+                (name, sum, workdays)
+            })
+            .collect::<Vec<_>>();
+        let per_week = i32::try_from(calendar.workdays.len()).unwrap_or_default();
+        if let (Some(hours), 1..) = (target, per_week) {
+            let mut balance = chrono::TimeDelta::zero();
+            for (_, sum, workdays) in entries.iter_mut() {
+                let hours = hours * *workdays / per_week;
+                balance += sum.billed() - hours;
+                sum.target = Some(Target {
+                    hours,
+                    balance: Some(balance),
+                });
+            }
+        }
+        Self {
+            title: "By week".to_owned(),
+            entries: Some(
+                entries
+                    .into_iter()
+                    .map(|(name, sum, _)| (name, sum))
+                    .collect(),
+            ),
+            total: None,
+        }
+    }
+
+    /// The time spent on projects with a budget, within the budget's date range.
+    pub(crate) fn budgets(
+        budgets: &BTreeMap<String, Budget>,
+        log: &Log,
+        rounding: Option<&Rounding>,
+    ) -> Self {
+        let entries = budgets
+            .iter()
+            .map(|(project, budget)| {
+                let Log(entries) = log.within(&budget.range);
+                let log = Log(entries
+                    .into_iter()
+                    .filter(|entry| &entry.project == project)
+                    .collect());
+                let sum = Sum {
+                    target: Some(Target {
+                        hours: duration(budget.hours),
+                        balance: None,
+                    }),
+                    ..Sum::of(&log, rounding)
+                };
+                (project.clone(), sum)
+            })
+            .collect();
+        Self {
+            title: "Budgets".to_owned(),
+            entries: Some(entries),
+            total: None,
+        }
+    }
+}
+
+/// Converts hours from the configuration to a duration, to the minute.
+pub(super) fn duration(hours: Decimal) -> chrono::TimeDelta {
+    let minutes = (hours * Decimal::from(60))
+        .round()
+        .to_i64()
+        .unwrap_or_default();
+    chrono::TimeDelta::minutes(minutes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{Render, Text};
+    use crate::timelog::Context;
+
+    fn parse(input: &str) -> Log {
+        Log::parse(input, &Context::default()).unwrap()
+    }
+
+    fn hours(hours: i64) -> chrono::TimeDelta {
+        chrono::TimeDelta::hours(hours)
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    static LOG: &str = indoc::indoc! {"
+        ## 2024-02-12
+        - 8-18 ABC
+        ## 2024-02-14
+        - 9-15 DEF
+        ## 2024-02-19
+        - 9-17 ABC
+    "};
+
+    #[test]
+    fn track_daily_target() {
+        let mut days = Report::days(&parse(LOG), Fill::Padded, None);
+        Report::track(&mut days, hours(8), &Calendar::default());
+        let balances = days
+            .iter()
+            .map(|(_, report)| report.total.unwrap().target.unwrap().balance.unwrap())
+            .collect::<Vec<_>>();
+        let expected = [2, -6, -8, -16, -24, -24, -24, -24]
+            .into_iter()
+            .map(hours)
+            .collect::<Vec<_>>();
+        assert_eq!(expected, balances);
+    }
+
+    #[test]
+    fn track_within_range() {
+        let range = DateRange(date(2024, 2, 12), date(2024, 2, 16));
+        let mut days = Report::days(&parse(LOG), Fill::Within(range), None);
+        Report::track(&mut days, hours(8), &Calendar::default());
+        // The range is padded, and days with entries after it are kept.
+        assert_eq!(6, days.len());
+        assert_eq!(date(2024, 2, 19), days[5].0);
+        let text = Text.report(&days[1].1);
+        assert!(text.ends_with(": 0 of 8 (-8, balance -6)\n"), "{}", text);
+    }
+
+    #[test]
+    fn by_week() {
+        let calendar = Calendar::default();
+        let report = Report::by_week(
+            &parse(LOG),
+            Fill::Padded,
+            None,
+            Some(hours(40)),
+            &calendar,
+            &BTreeMap::new(),
+        );
+        let entries = report.entries.unwrap();
+        assert_eq!(
+            vec!["2024-W07".to_owned(), "2024-W08".to_owned()],
+            entries
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(hours(16), entries[0].1.raw);
+        // The second week only has its Monday in the log, so its target is 8 hours.
+        assert_eq!(Some(hours(-24)), entries[1].1.target.unwrap().balance);
+    }
+
+    #[test]
+    fn budgets() {
+        let budgets = BTreeMap::from([(
+            "ABC".to_owned(),
+            Budget {
+                hours: Decimal::from(12),
+                range: DateRange(date(2024, 2, 1), date(2024, 2, 18)),
+            },
+        )]);
+        let report = Report::budgets(&budgets, &parse(LOG), None);
+        let result = Text.report(&report);
+        assert!(result.ends_with("ABC: 10 of 12 (2 left)\n"), "{}", result);
+    }
+}
@@ -0,0 +1,273 @@
+use super::format_hours;
+use crate::timelog::{Log, Time};
+use colored::{Color, Colorize};
+use std::fmt::Write;
+
+/// Minutes per column to choose from, from the finest to the coarsest.
+static STEPS: [i64; 8] = [5, 10, 15, 20, 30, 60, 120, 240];
+
+/// Background colours of project blocks, in the order projects first appear.
+static COLORS: [Color; 6] = [
+    Color::Blue,
+    Color::Green,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Yellow,
+    Color::BrightBlack,
+];
+
+/// A time axis per day, with a block per project, for the terminal.
+pub struct Timeline {
+    /// The number of columns to fit the timeline in.
+    pub width: usize,
+}
+
+/// What a column of the timeline shows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Cell {
+    Gap,
+    /// The index of the project in the order projects first appear in the log.
+    Project(usize),
+    Overlap,
+}
+
+/// A day's timed entries as minutes since midnight, and the hours the axis covers.
+struct Day {
+    entries: Vec<(i64, i64, usize)>,
+    start: i64,
+    end: i64,
+}
+
+impl Timeline {
+    pub fn render(&self, log: &Log) -> String {
+        let projects = log.by_project().into_keys().collect::<Vec<_>>();
+        log.by_date()
+            .into_iter()
+            .map(|(date, log)| self.day(&date, &log, &projects))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn day(&self, date: &chrono::NaiveDate, log: &Log, projects: &[String]) -> String {
+        let mut output = String::new();
+        let index = |project: &String| projects.iter().position(|p| p == project).unwrap_or(0);
+        writeln!(
+            output,
+            "{}: {}",
+            date.to_string().bold(),
+            format_hours(&log.sum_duration())
+        )
+        .unwrap();
+
+        if let Some(day) = Day::new(date, log, index) {
+            let step = self.step(day.end - day.start);
+            let cells = day.cells(step);
+            writeln!(output, "{}", axis(day.start, step, cells.len())).unwrap();
+            let mut line = String::new();
+            for (cell, length) in runs(&cells) {
+                let block = match cell {
+                    Cell::Gap => "░".repeat(length).dimmed(),
+                    Cell::Overlap => "!".repeat(length).bold().white().on_red(),
+                    Cell::Project(index) => label(&projects[index], length)
+                        .black()
+                        .on_color(COLORS[index % COLORS.len()]),
+                };
+                write!(line, "{}", block).unwrap();
+            }
+            writeln!(output, "{}", line).unwrap();
+        }
+
+        let mut legend = vec![];
+        for (project, log) in log.by_project() {
+            if log.0.iter().all(|entry| entry.time == Time::AllDay) {
+                continue;
+            }
+            let timed = log.0.iter().all(|entry| entry.from().is_some());
+            let name = match timed {
+                true => project
+                    .black()
+                    .on_color(COLORS[index(&project) % COLORS.len()]),
+                false => project.normal(),
+            };
+            legend.push(format!("{} {}", name, format_hours(&log.sum_duration())));
+        }
+        if !legend.is_empty() {
+            writeln!(output, "{}", legend.join("  ")).unwrap();
+        }
+        for entry in log.0.iter().filter(|entry| entry.from().is_none()) {
+            match entry.time {
+                Time::AllDay => writeln!(output, "all day: {}", entry.project).unwrap(),
+                _ => writeln!(output, "+ {} {}", entry.time, entry.project).unwrap(),
+            }
+        }
+        output
+    }
+
+    /// The finest number of minutes per column that fits the span and the last label.
+    fn step(&self, span: i64) -> i64 {
+        let columns = i64::try_from(self.width).unwrap_or(i64::MAX) - 5;
+        STEPS
+            .into_iter()
+            .find(|step| (span + step - 1) / step <= columns)
+            .unwrap_or(STEPS[STEPS.len() - 1])
+    }
+}
+
+impl Day {
+    fn new(date: &chrono::NaiveDate, log: &Log, index: impl Fn(&String) -> usize) -> Option<Self> {
+        let midnight = date.and_time(chrono::NaiveTime::MIN);
+        let entries = log
+            .0
+            .iter()
+            .filter_map(|entry| {
+                let from = (entry.from()? - midnight).num_minutes();
+                let until = (entry.until()? - midnight).num_minutes();
+                Some((from, until, index(&entry.project)))
+            })
+            .filter(|(from, until, _)| from < until)
+            .collect::<Vec<_>>();
+        let start = entries.iter().map(|(from, _, _)| *from).min()?;
+        let end = entries.iter().map(|(_, until, _)| *until).max()?;
+        Some(Self {
+            entries,
+            start: start.div_euclid(60) * 60,
+            end: (end + 59).div_euclid(60) * 60,
+        })
+    }
+
+    /// A cell per column of `step` minutes. A column is an overlap if entries overlap in any of
+    /// its minutes, and otherwise shows what takes up most of it.
+    fn cells(&self, step: i64) -> Vec<Cell> {
+        let minute = |minute: i64| {
+            let mut covering = self
+                .entries
+                .iter()
+                .filter(|(from, until, _)| *from <= minute && minute < *until);
+            match (covering.next(), covering.next()) {
+                (None, _) => Cell::Gap,
+                (Some((_, _, project)), None) => Cell::Project(*project),
+                (Some(_), Some(_)) => Cell::Overlap,
+            }
+        };
+        (self.start..self.end)
+            .step_by(step as usize)
+            .map(|from| {
+                // The minutes per cell, in the order cells first appear in the column.
+                let mut counts: Vec<(Cell, usize)> = vec![];
+                for cell in (from..(from + step).min(self.end)).map(minute) {
+                    match (cell, counts.iter_mut().find(|(other, _)| *other == cell)) {
+                        (Cell::Overlap, _) => return Cell::Overlap,
+                        (_, Some((_, count))) => *count += 1,
+                        (_, None) => counts.push((cell, 1)),
+                    }
+                }
+                // Projects win ties, so short entries don't disappear in gaps.
+                counts
+                    .into_iter()
+                    .max_by_key(|(cell, count)| (*count, *cell != Cell::Gap))
+                    .map_or(Cell::Gap, |(cell, _)| cell)
+            })
+            .collect()
+    }
+}
+
+/// Consecutive columns with the same cell, with their length.
+fn runs(cells: &[Cell]) -> Vec<(Cell, usize)> {
+    let mut runs: Vec<(Cell, usize)> = vec![];
+    for cell in cells {
+        match runs.last_mut() {
+            Some((last, length)) if last == cell => *length += 1,
+            _ => runs.push((*cell, 1)),
+        }
+    }
+    runs
+}
+
+/// The hours above the columns, with a tick per hour, skipping labels that don't fit.
+fn axis(start: i64, step: i64, columns: usize) -> String {
+    let mut labels = String::new();
+    let mut ticks = String::new();
+    for column in 0..=columns {
+        let minutes = start + column as i64 * step;
+        let hour = minutes % 60 == 0;
+        if hour && labels.chars().count() <= column {
+            let padding = column - labels.chars().count();
+            write!(labels, "{}{}:00 ", " ".repeat(padding), minutes / 60 % 24).unwrap();
+            ticks.push('|');
+        } else if hour {
+            ticks.push('.');
+        } else {
+            ticks.push(' ');
+        }
+    }
+    format!("{}\n{}", labels.trim_end(), ticks.trim_end())
+}
+
+/// A project's name in a block of columns, starting with a separator, cut off if it doesn't fit.
+fn label(project: &str, length: usize) -> String {
+    format!("|{}", project)
+        .chars()
+        .chain(std::iter::repeat(' '))
+        .take(length)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timelog::Context;
+    use regex::Regex;
+
+    fn parse(input: &str) -> Log {
+        Log::parse(input, &Context::default()).unwrap()
+    }
+
+    fn plain(text: &str) -> String {
+        Regex::new("\x1b\\[[0-9;]*m")
+            .unwrap()
+            .replace_all(text, "")
+            .into_owned()
+    }
+
+    #[test]
+    fn cells() {
+        let log = parse("## 2024-02-13\n- 9-10 ABC\n- 10:30-11:30 DEF\n- 11-12 ABC\n");
+        let projects = log.by_project().into_keys().collect::<Vec<_>>();
+        let date = log.0[0].date;
+        let index = |project: &String| projects.iter().position(|p| p == project).unwrap();
+        let day = Day::new(&date, &log, index).unwrap();
+        assert_eq!((540, 720), (day.start, day.end));
+        let expected = vec![
+            Cell::Project(0),
+            Cell::Project(0),
+            Cell::Gap,
+            Cell::Project(1),
+            Cell::Overlap,
+            Cell::Project(0),
+        ];
+        assert_eq!(expected, day.cells(30));
+    }
+
+    #[test]
+    fn step() {
+        assert_eq!(10, Timeline { width: 80 }.step(9 * 60));
+        assert_eq!(5, Timeline { width: 120 }.step(8 * 60));
+        assert_eq!(240, Timeline { width: 10 }.step(24 * 60));
+    }
+
+    #[test]
+    fn render() {
+        let log = parse("## 2024-02-13\n- 9-10 ABC\n- 11-12 DEF\n- 1h GHI\n");
+        let output = plain(&Timeline { width: 40 }.render(&log));
+        let expected = [
+            "2024-02-13: 3",
+            "9:00  10:00 11:00 12:00",
+            "|     |     |     |",
+            "|ABC  ░░░░░░|DEF  ",
+            "ABC 1  DEF 1  GHI 1",
+            "+ 1h00 GHI\n",
+        ]
+        .join("\n");
+        assert_eq!(expected, output);
+    }
+}
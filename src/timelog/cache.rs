@@ -0,0 +1,167 @@
+use super::{entry::EntryRepr, Context, Entry, Log, Warning};
+use crate::Error;
+use std::{
+    env, fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::UNIX_EPOCH,
+};
+
+/// Tells apart the temporary files of logs that are cached at the same time.
+static WRITES: AtomicUsize = AtomicUsize::new(0);
+
+/// Parsed logs on disk, with a file per log. A cached log is used as long as the file has the
+/// same modification time and size, and is parsed with the same context by the same version of
+/// `myw`. Failing to read or write the cache just means parsing again.
+pub struct Cache {
+    directory: PathBuf,
+}
+
+/// What a cached log was parsed from, to tell whether it's still valid.
+#[derive(PartialEq, serde::Serialize, serde::Deserialize)]
+struct Key {
+    version: String,
+    path: PathBuf,
+    modified: (u64, u32),
+    size: u64,
+    context: String,
+}
+
+/// A parsed log, stored without the impls of the `serde` feature, so the cache doesn't need it.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Cached {
+    key: Key,
+    entries: Vec<EntryRepr>,
+    /// The line and message of each warning.
+    warnings: Vec<(usize, String)>,
+}
+
+impl Cache {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    /// `$MYW_CACHE_DIR`, or `myw` in `$XDG_CACHE_HOME` (defaulting to `~/.cache`).
+    pub fn default_directory() -> Option<PathBuf> {
+        if let Some(path) = env::var_os("MYW_CACHE_DIR") {
+            return Some(PathBuf::from(path));
+        }
+        let dir = env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?;
+        Some(dir.join("myw"))
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Parses the log in a file, unless it's cached and hasn't changed since.
+    pub fn parse(&self, file: &Path, context: &Context) -> Result<(Log, Vec<Warning>), Error> {
+        let metadata = fs::metadata(file).map_err(|err| Error::io(file, err))?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .unwrap_or_default();
+        let key = Key {
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            path: fs::canonicalize(file).unwrap_or_else(|_| file.to_owned()),
+            modified: (modified.as_secs(), modified.subsec_nanos()),
+            size: metadata.len(),
+            context: format!("{:?}", context),
+        };
+        let path = self.path(&key.path);
+        if let Some(cached) = fs::read(&path)
+            .ok()
+            .and_then(|content| serde_json::from_slice::<Cached>(&content).ok())
+            .filter(|cached| cached.key == key)
+        {
+            let entries = cached.entries.into_iter().map(Entry::try_from);
+            if let Ok(entries) = entries.collect::<Result<Vec<_>, _>>() {
+                let warnings = cached.warnings.into_iter();
+                let warnings = warnings.map(|(line, message)| Warning { line, message });
+                return Ok((Log::from(entries), warnings.collect()));
+            }
+        }
+        let content = fs::read_to_string(file).map_err(|err| Error::io(file, err))?;
+        let (log, warnings) =
+            Log::parse_with_warnings(&content, context).map_err(|err| err.in_file(file))?;
+        let cached = Cached {
+            key,
+            entries: log.iter().cloned().map(EntryRepr::from).collect(),
+            warnings: warnings
+                .iter()
+                .map(|warning| (warning.line, warning.message.clone()))
+                .collect(),
+        };
+        // Written next to the cached log and then moved, so readers never see half a file.
+        let temporary = path.with_extension(format!(
+            "{}-{}.tmp",
+            std::process::id(),
+            WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::create_dir_all(&self.directory)
+            .and_then(|_| fs::write(&temporary, serde_json::to_vec(&cached)?))
+            .and_then(|_| fs::rename(&temporary, &path));
+        Ok((log, warnings))
+    }
+
+    /// Removes all cached logs, returning how many there were.
+    pub fn clear(&self) -> Result<usize, Error> {
+        let entries = match fs::read_dir(&self.directory) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(Error::io(&self.directory, err)),
+        };
+        let mut count = 0;
+        for entry in entries {
+            let path = entry.map_err(|err| Error::io(&self.directory, err))?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                fs::remove_file(&path).map_err(|err| Error::io(&path, err))?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// The file a log is cached in, named after a hash of its path.
+    fn path(&self, file: &Path) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        file.hash(&mut hasher);
+        self.directory
+            .join(format!("{:016x}.json", hasher.finish()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_again_after_changes() {
+        let directory = env::temp_dir().join(format!("myw-cache-test-{}", std::process::id()));
+        let cache = Cache::new(directory.join("cache"));
+        let file = directory.join("2024-w07.md");
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(&file, "## Tuesday\n- 9-10 ABC\n").unwrap();
+        let context = Context::from_path(&file);
+
+        let (log, warnings) = cache.parse(&file, &context).unwrap();
+        assert_eq!(1, log.len());
+        assert!(warnings.is_empty());
+        assert_eq!((log.clone(), vec![]), cache.parse(&file, &context).unwrap());
+        // Parsed with a different context, the heading can't be resolved.
+        let (_, warnings) = cache.parse(&file, &Context::default()).unwrap();
+        assert_eq!(1, warnings.len());
+
+        fs::write(&file, "## Tuesday\n- 9-10 ABC\n- 10-11 DEF\n").unwrap();
+        assert_eq!(2, cache.parse(&file, &context).unwrap().0.len());
+        assert_eq!(1, cache.clear().unwrap());
+        assert_eq!(0, cache.clear().unwrap());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,106 @@
+use super::HeadingFormats;
+use crate::utils::DateRange;
+use std::path::Path;
+
+/// Information about where a log comes from, used to resolve dates while parsing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Context {
+    /// The range of dates the log is expected to cover, e.g. the week of a `2024-w07.md` file.
+    pub range: Option<DateRange>,
+    /// How deeply nested (in block quotes or sub-lists) entries may be to still be found. Only
+    /// top-level lists are considered at depth 0.
+    pub depth: usize,
+    /// How dates are written in headings.
+    pub headings: HeadingFormats,
+    pub reader: Reader,
+}
+
+/// How logs are read, from the `reader` key in the configuration.
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Reader {
+    /// Parses the whole document as CommonMark.
+    #[default]
+    Markdown,
+    /// Scans lines for headings and list items, which is several times faster. Text with inline
+    /// markup like `**ABC**`, or spanning lines, is parsed as markdown, so entries come out the
+    /// same as with `Markdown`. HTML blocks and lists that start with an empty item aren't
+    /// recognised.
+    Lines,
+    /// Scans lines like `Lines`, reading all text as is, so `**ABC**` isn't a project.
+    PlainLines,
+}
+
+impl Context {
+    pub fn from_path(path: &Path) -> Self {
+        let range = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(DateRange::from_file_name);
+        Self {
+            range,
+            ..Default::default()
+        }
+    }
+
+    /// Resolves a weekday to a date, as long as it occurs exactly once in the range.
+    pub(super) fn resolve_weekday(
+        &self,
+        weekday: chrono::Weekday,
+    ) -> Result<chrono::NaiveDate, String> {
+        let Some(range) = &self.range else {
+            return Err(format!(
+                "cannot resolve \"{}\" without a date in the file name",
+                weekday
+            ));
+        };
+        let mut dates = range
+            .clone()
+            .filter(|date| chrono::Datelike::weekday(date) == weekday);
+        match (dates.next(), dates.next()) {
+            (Some(date), None) => Ok(date),
+            (Some(_), Some(_)) => Err(format!("\"{}\" is ambiguous in {}", weekday, range)),
+            (None, _) => Err(format!("\"{}\" does not occur in {}", weekday, range)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn date(y: i32, m: u32, d: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn from_path() {
+        let context = Context::from_path(&PathBuf::from("logs/2024-w07.md"));
+        let expected = Some(DateRange(date(2024, 2, 12), date(2024, 2, 18)));
+        assert_eq!(expected, context.range);
+        let context = Context::from_path(&PathBuf::from("logs/notes.md"));
+        assert_eq!(None, context.range);
+    }
+
+    #[test]
+    fn resolve_weekday() {
+        let context = Context::from_path(&PathBuf::from("2024-w07.md"));
+        assert_eq!(
+            Ok(date(2024, 2, 13)),
+            context.resolve_weekday(chrono::Weekday::Tue)
+        );
+    }
+
+    #[test]
+    fn resolve_weekday_ambiguous() {
+        let context = Context::from_path(&PathBuf::from("2024-02.md"));
+        assert!(context.resolve_weekday(chrono::Weekday::Tue).is_err());
+    }
+
+    #[test]
+    fn resolve_weekday_without_range() {
+        let context = Context::default();
+        assert!(context.resolve_weekday(chrono::Weekday::Tue).is_err());
+    }
+}
@@ -1,60 +1,478 @@
+use crate::Error;
 use std::{cmp, fmt, sync::LazyLock};
 
 static ENTRY_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
-    regex::Regex::new(
+    regex::Regex::new(&format!(
         r#"(?x)^\s*
-        (?<from_h>[012]?\d)(?::?(?<from_m>\d{2}))?
-        (?:\s*-\s*|\s+)
-        (?<until_h>[012]?\d)(?::?(?<until_m>\d{2}))?
+        (?:
+            {from}(?:\s*-\s*|\s+){until}
+            |{start}\s*\+\s*{added}
+            |{duration}
+            |(?<all_day>(?i:all[\s-]day))
+        )
         (?:\s*:\s*|\s+)
         (?:
-            (?<project>\w{3,}+)
+            (?<project>\w{{3,}}+)
             |(?:"(?<quoted_project>.+?)")
         )
         \s*(?<notes>.+?)?\s*$"#,
-    )
+        from = time_pattern("from"),
+        until = time_pattern("until"),
+        start = time_pattern("start"),
+        added = duration_pattern("added"),
+        duration = duration_pattern("duration"),
+    ))
     .unwrap()
 });
 
+/// The most hours a duration in an entry can have, so sums of entries can't overflow.
+static MAX_HOURS: i64 = 10_000;
+
+static PROJECT_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"^\w{3,}$").unwrap());
+
+static TAG_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"(?:^|\s)#([\w-]+)").unwrap());
+
+/// A clock time like `9`, `0930`, `9:30` or `9:30pm`, with its parts in named groups.
+fn time_pattern(name: &str) -> String {
+    format!(
+        r"(?<{name}_h>[012]?\d)(?::?(?<{name}_m>\d{{2}}))?(?:\s*(?<{name}_meridiem>(?i:[ap]))\.?(?i:m)\.?)?"
+    )
+}
+
+/// A duration like `1h30`, `1.5h`, `2 hours`, `45m` or `90 min`, with its parts in named groups.
+fn duration_pattern(name: &str) -> String {
+    format!(
+        r"(?:
+            (?<{name}_h>\d+(?:[.,]\d+)?)\s*h(?:ours?|rs?)?
+            (?:\s*(?<{name}_hm>\d{{1,2}})(?:\s*m(?:in(?:utes?)?)?)?)?
+            |(?<{name}_m>\d+)\s*m(?:in(?:utes?)?)?
+        )"
+    )
+}
+
+/// Time spent on a project on a date. Entries are made by parsing a log, with [`Entry::new`]
+/// or with [`Entry::builder`].
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "EntryRepr", try_from = "EntryRepr")
+)]
 pub struct Entry {
-    pub from: chrono::NaiveDateTime,
-    pub until: chrono::NaiveDateTime,
-    pub project: String,
-    pub notes: Option<String>,
+    pub(crate) date: chrono::NaiveDate,
+    pub(crate) time: Time,
+    pub(crate) project: String,
+    pub(crate) notes: Option<String>,
+}
+
+/// When an entry's time was spent: between two clock times, or just for a while on its date.
+/// All-day entries mark days off, like leave, and don't count as time spent.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Time {
+    Interval {
+        from: chrono::NaiveDateTime,
+        until: chrono::NaiveDateTime,
+    },
+    Duration(chrono::TimeDelta),
+    AllDay,
 }
 
 impl Entry {
+    /// An entry without notes. Intervals must end after they start, durations must be positive,
+    /// and neither may be longer than 10,000 hours. Projects can't be empty or span lines.
+    pub fn new(
+        date: chrono::NaiveDate,
+        time: Time,
+        project: impl Into<String>,
+    ) -> Result<Self, Error> {
+        let project = project.into();
+        if project.trim().is_empty() || project.contains('\n') {
+            return Err(Error::Entry(format!("invalid project \"{}\"", project)));
+        }
+        let duration = time.duration();
+        if time != Time::AllDay && duration <= chrono::TimeDelta::zero() {
+            return Err(Error::Entry(format!("{} is not a positive duration", time)));
+        }
+        if duration.num_hours() > MAX_HOURS {
+            return Err(Error::Entry(format!(
+                "{} is longer than {} hours",
+                time, MAX_HOURS
+            )));
+        }
+        Ok(Self {
+            date,
+            time,
+            project,
+            notes: None,
+        })
+    }
+
+    pub fn builder(project: impl Into<String>) -> EntryBuilder {
+        EntryBuilder {
+            project: project.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_notes(self, notes: impl Into<String>) -> Self {
+        Self {
+            notes: Some(notes.into()),
+            ..self
+        }
+    }
+
+    pub fn date(&self) -> chrono::NaiveDate {
+        self.date
+    }
+
+    pub fn time(&self) -> &Time {
+        &self.time
+    }
+
+    pub fn project(&self) -> &str {
+        &self.project
+    }
+
+    pub fn notes(&self) -> Option<&str> {
+        self.notes.as_deref()
+    }
+
     pub fn parse(s: &str, date: &chrono::NaiveDate) -> Option<Self> {
         let cap = ENTRY_RE.captures(s)?;
-        let from = time(&cap, "from_h", "from_m")?;
-        let until = time(&cap, "until_h", "until_m")?;
+        let time = if cap.name("from_h").is_some() {
+            let until = time(&cap, "until")?;
+            let from = time_before(&cap, "from", until)?;
+            Time::Interval {
+                from: date.and_time(from),
+                until: date.and_time(until),
+            }
+        } else if cap.name("start_h").is_some() {
+            let from = date.and_time(time(&cap, "start")?);
+            Time::Interval {
+                from,
+                until: from.checked_add_signed(duration(&cap, "added")?)?,
+            }
+        } else if cap.name("all_day").is_some() {
+            Time::AllDay
+        } else {
+            Time::Duration(duration(&cap, "duration")?)
+        };
         let project = cap.name("project").or(cap.name("quoted_project")).unwrap();
-        (from < until).then(|| Entry {
-            from: chrono::NaiveDateTime::new(*date, from),
-            until: chrono::NaiveDateTime::new(*date, until),
+        (time == Time::AllDay || chrono::TimeDelta::zero() < time.duration()).then(|| Entry {
+            date: *date,
+            time,
             project: project.as_str().into(),
             notes: cap.name("notes").map(|m| m.as_str().into()),
         })
     }
+
+    /// When the entry started, unless only its duration is known.
+    pub fn from(&self) -> Option<chrono::NaiveDateTime> {
+        match self.time {
+            Time::Interval { from, .. } => Some(from),
+            Time::Duration(_) | Time::AllDay => None,
+        }
+    }
+
+    /// When the entry ended, unless only its duration is known.
+    pub fn until(&self) -> Option<chrono::NaiveDateTime> {
+        match self.time {
+            Time::Interval { until, .. } => Some(until),
+            Time::Duration(_) | Time::AllDay => None,
+        }
+    }
+
+    pub fn duration(&self) -> chrono::TimeDelta {
+        self.time.duration()
+    }
+
+    /// Whether the clock times of two entries overlap. Entries without clock times don't overlap
+    /// with anything.
+    pub fn overlaps(&self, other: &Entry) -> bool {
+        match (&self.time, &other.time) {
+            (
+                Time::Interval { from, until },
+                Time::Interval {
+                    from: other_from,
+                    until: other_until,
+                },
+            ) => from < other_until && other_from < until,
+            _ => false,
+        }
+    }
+
+    /// Hashtags in the notes (like `#review`), without the `#`.
+    pub fn tags(&self) -> Vec<&str> {
+        let Some(notes) = &self.notes else {
+            return vec![];
+        };
+        TAG_RE
+            .captures_iter(notes)
+            .map(|cap| cap.get(1).unwrap().as_str())
+            .collect()
+    }
+
+    /// Formats the entry as the text of a list item, such that parsing it again with the same date
+    /// results in an equal entry. Intervals past midnight are written as a start plus duration.
+    pub fn to_markdown(&self) -> String {
+        let time = match self.time {
+            Time::Interval { from, until } if from.date() == until.date() => {
+                format!("{} - {}", from.format("%H:%M"), until.format("%H:%M"))
+            }
+            Time::Interval { from, until } => format!(
+                "{} +{}",
+                from.format("%H:%M"),
+                format_duration(until - from)
+            ),
+            Time::Duration(duration) => format_duration(duration),
+            Time::AllDay => "all day".to_owned(),
+        };
+        let project = if PROJECT_RE.is_match(&self.project) {
+            self.project.clone()
+        } else {
+            format!("\"{}\"", self.project.replace('"', "'"))
+        };
+        match &self.notes {
+            Some(notes) => format!("{}: {} {}", time, project, notes.replace('\n', " ")),
+            None => format!("{}: {}", time, project),
+        }
+    }
+}
+
+fn format_duration(duration: chrono::TimeDelta) -> String {
+    match (duration.num_hours(), duration.num_minutes() % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h{:02}", h, m),
+    }
+}
+
+/// How entries are serialized: the date, the kind of time with its clock times or duration in
+/// minutes, the project and the notes, if any. Datetimes are ISO 8601 without an offset, like
+/// `2024-02-13T09:00:00`. The cache stores entries like this too, with or without the `serde`
+/// feature.
+#[cfg(any(feature = "serde", feature = "cache"))]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct EntryRepr {
+    date: chrono::NaiveDate,
+    #[serde(flatten)]
+    time: TimeRepr,
+    project: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    notes: Option<String>,
+}
+
+#[cfg(any(feature = "serde", feature = "cache"))]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(tag = "time", rename_all = "snake_case")]
+enum TimeRepr {
+    Interval {
+        from: chrono::NaiveDateTime,
+        until: chrono::NaiveDateTime,
+    },
+    Duration {
+        #[serde(with = "crate::utils::minutes")]
+        minutes: chrono::TimeDelta,
+    },
+    AllDay,
+}
+
+#[cfg(any(feature = "serde", feature = "cache"))]
+impl From<Entry> for EntryRepr {
+    fn from(entry: Entry) -> Self {
+        let time = match entry.time {
+            Time::Interval { from, until } => TimeRepr::Interval { from, until },
+            Time::Duration(minutes) => TimeRepr::Duration { minutes },
+            Time::AllDay => TimeRepr::AllDay,
+        };
+        Self {
+            date: entry.date,
+            time,
+            project: entry.project,
+            notes: entry.notes,
+        }
+    }
+}
+
+#[cfg(any(feature = "serde", feature = "cache"))]
+impl TryFrom<EntryRepr> for Entry {
+    type Error = Error;
+
+    fn try_from(repr: EntryRepr) -> Result<Self, Self::Error> {
+        let time = match repr.time {
+            TimeRepr::Interval { from, until } => Time::Interval { from, until },
+            TimeRepr::Duration { minutes } => Time::Duration(minutes),
+            TimeRepr::AllDay => Time::AllDay,
+        };
+        let entry = Self::new(repr.date, time, repr.project)?;
+        Ok(Self {
+            notes: repr.notes,
+            ..entry
+        })
+    }
+}
+
+/// Builds an entry from clock times or a duration, like `Entry::builder("ABC").on(date)
+/// .from(nine).lasting(hour).build()`.
+#[derive(Clone, Debug, Default)]
+pub struct EntryBuilder {
+    project: String,
+    date: Option<chrono::NaiveDate>,
+    from: Option<chrono::NaiveTime>,
+    until: Option<chrono::NaiveTime>,
+    duration: Option<chrono::TimeDelta>,
+    all_day: bool,
+    notes: Option<String>,
+}
+
+impl EntryBuilder {
+    pub fn on(self, date: chrono::NaiveDate) -> Self {
+        Self {
+            date: Some(date),
+            ..self
+        }
+    }
+
+    pub fn from(self, time: chrono::NaiveTime) -> Self {
+        Self {
+            from: Some(time),
+            ..self
+        }
+    }
+
+    pub fn until(self, time: chrono::NaiveTime) -> Self {
+        Self {
+            until: Some(time),
+            ..self
+        }
+    }
+
+    pub fn lasting(self, duration: chrono::TimeDelta) -> Self {
+        Self {
+            duration: Some(duration),
+            ..self
+        }
+    }
+
+    pub fn all_day(self) -> Self {
+        Self {
+            all_day: true,
+            ..self
+        }
+    }
+
+    pub fn notes(self, notes: impl Into<String>) -> Self {
+        Self {
+            notes: Some(notes.into()),
+            ..self
+        }
+    }
+
+    /// The entry, if it has a date and exactly one way of telling its time: all day, a start
+    /// and an end, a start and a duration, or only a duration.
+    pub fn build(self) -> Result<Entry, Error> {
+        let date = self
+            .date
+            .ok_or_else(|| Error::Entry("an entry needs a date".to_owned()))?;
+        let time = match (self.all_day, self.from, self.until, self.duration) {
+            (true, None, None, None) => Time::AllDay,
+            (false, Some(from), Some(until), None) => Time::Interval {
+                from: date.and_time(from),
+                until: date.and_time(until),
+            },
+            (false, Some(from), None, Some(duration)) => {
+                let from = date.and_time(from);
+                let until = from
+                    .checked_add_signed(duration)
+                    .ok_or_else(|| Error::Entry(format!("{} ends too late", from)))?;
+                Time::Interval { from, until }
+            }
+            (false, None, None, Some(duration)) => Time::Duration(duration),
+            _ => {
+                return Err(Error::Entry(
+                    "an entry needs to be all day, or have a start and an end or a duration"
+                        .to_owned(),
+                ))
+            }
+        };
+        let entry = Entry::new(date, time, self.project)?;
+        Ok(match self.notes {
+            Some(notes) => entry.with_notes(notes),
+            None => entry,
+        })
+    }
+}
+
+impl Time {
     pub fn duration(&self) -> chrono::TimeDelta {
-        self.until.signed_duration_since(self.from)
+        match self {
+            Self::Interval { from, until } => until.signed_duration_since(*from),
+            Self::Duration(duration) => *duration,
+            Self::AllDay => chrono::TimeDelta::zero(),
+        }
     }
 }
 
-fn time(cap: &regex::Captures, h: &str, m: &str) -> Option<chrono::NaiveTime> {
-    let h = time_part(cap.name(h));
-    let m = time_part(cap.name(m));
+fn time(cap: &regex::Captures, name: &str) -> Option<chrono::NaiveTime> {
+    let h = time_part(cap.name(&format!("{name}_h")));
+    let m = time_part(cap.name(&format!("{name}_m")));
+    let h = match cap.name(&format!("{name}_meridiem")) {
+        Some(meridiem) => meridiem_hour(h, meridiem.as_str())?,
+        None => h,
+    };
     chrono::NaiveTime::from_hms_opt(h, m, 0)
 }
 
+/// Parses the start of a range, which may borrow the end's am/pm as in `10-11am` or `1-3pm`, as
+/// long as that still puts it before the end.
+fn time_before(
+    cap: &regex::Captures,
+    name: &str,
+    until: chrono::NaiveTime,
+) -> Option<chrono::NaiveTime> {
+    let from = time(cap, name)?;
+    let (None, Some(meridiem)) = (
+        cap.name(&format!("{name}_meridiem")),
+        cap.name("until_meridiem"),
+    ) else {
+        return Some(from);
+    };
+    let borrowed = meridiem_hour(chrono::Timelike::hour(&from), meridiem.as_str())
+        .and_then(|h| chrono::Timelike::with_hour(&from, h))
+        .filter(|borrowed| *borrowed < until);
+    Some(borrowed.unwrap_or(from))
+}
+
+fn meridiem_hour(h: u32, meridiem: &str) -> Option<u32> {
+    match (h, meridiem.eq_ignore_ascii_case("p")) {
+        (0 | 13.., _) => None,
+        (12, pm) => Some(if pm { 12 } else { 0 }),
+        (h, pm) => Some(if pm { h + 12 } else { h }),
+    }
+}
+
+fn duration(cap: &regex::Captures, name: &str) -> Option<chrono::TimeDelta> {
+    let minutes = match cap.name(&format!("{name}_m")) {
+        Some(m) => m.as_str().parse().ok()?,
+        None => {
+            let h = cap.name(&format!("{name}_h"))?.as_str().replace(',', ".");
+            let m = time_part(cap.name(&format!("{name}_hm")));
+            ((h.parse::<f64>().ok()? * 60.0).round() as i64).checked_add(i64::from(m))?
+        }
+    };
+    chrono::TimeDelta::try_minutes(minutes).filter(|duration| duration.num_hours() <= MAX_HOURS)
+}
+
 fn time_part(s: Option<regex::Match>) -> u32 {
     s.map_or(0, |s| s.as_str().parse::<u32>().unwrap_or(0))
 }
 
 impl cmp::PartialEq for Entry {
     fn eq(&self, other: &Self) -> bool {
-        self.from == other.from && self.until == other.until && self.project == other.project
+        self.date == other.date && self.time == other.time && self.project == other.project
     }
 }
 
@@ -62,9 +480,9 @@ impl cmp::Eq for Entry {}
 
 impl cmp::Ord for Entry {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
-        self.from
-            .cmp(&other.from)
-            .then_with(|| self.until.cmp(&other.until))
+        self.date
+            .cmp(&other.date)
+            .then_with(|| self.time.cmp(&other.time))
             .then_with(|| self.project.cmp(&other.project))
     }
 }
@@ -77,17 +495,31 @@ impl cmp::PartialOrd for Entry {
 
 impl fmt::Display for Entry {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let date = self.from.format("%Y-%m-%d");
-        let from = self.from.format("%H:%M");
-        let until = self.until.format("%H:%M");
+        write!(
+            f,
+            "{} | {}: {}",
+            self.date.format("%Y-%m-%d"),
+            self.time,
+            self.project
+        )?;
         if let Some(notes) = &self.notes {
-            write!(
-                f,
-                "{} | {} - {}: {} - {}",
-                date, from, until, self.project, notes
-            )
-        } else {
-            write!(f, "{} | {} - {}: {}", date, from, until, self.project)
+            write!(f, " - {}", notes)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Interval { from, until } => {
+                write!(f, "{} - {}", from.format("%H:%M"), until.format("%H:%M"))
+            }
+            Self::Duration(duration) => {
+                let minutes = duration.num_minutes();
+                write!(f, "{}h{:02}", minutes / 60, minutes % 60)
+            }
+            Self::AllDay => write!(f, "all day"),
         }
     }
 }
@@ -104,6 +536,21 @@ mod tests {
         chrono::NaiveDateTime::new(*DATE, time)
     }
 
+    fn interval(from: chrono::NaiveDateTime, until: chrono::NaiveDateTime) -> Time {
+        Time::Interval { from, until }
+    }
+
+    #[test]
+    fn overlaps() {
+        let entry = |from, until| Entry::new(*DATE, interval(from, until), "ABC").unwrap();
+        let morning = entry(datetime(9, 0), datetime(12, 0));
+        assert!(morning.overlaps(&entry(datetime(11, 0), datetime(13, 0))));
+        assert!(morning.overlaps(&entry(datetime(10, 0), datetime(11, 0))));
+        assert!(!morning.overlaps(&entry(datetime(12, 0), datetime(13, 0))));
+        let duration = Entry::new(*DATE, Time::Duration(chrono::TimeDelta::hours(2)), "ABC");
+        assert!(!morning.overlaps(&duration.unwrap()));
+    }
+
     #[test]
     fn unrelated() {
         let result = Entry::parse("some unrelated list item", &DATE);
@@ -115,8 +562,8 @@ mod tests {
         let result = Entry::parse("9:00 - 10:45: ABC", &DATE);
         assert_eq!(
             Some(Entry {
-                from: datetime(9, 0),
-                until: datetime(10, 45),
+                date: *DATE,
+                time: interval(datetime(9, 0), datetime(10, 45)),
                 project: "ABC".into(),
                 notes: None
             }),
@@ -129,8 +576,8 @@ mod tests {
         let result = Entry::parse("9:00 - 10:45: DEF some notes here", &DATE);
         assert_eq!(
             Some(Entry {
-                from: datetime(9, 0),
-                until: datetime(10, 45),
+                date: *DATE,
+                time: interval(datetime(9, 0), datetime(10, 45)),
                 project: "DEF".into(),
                 notes: Some("some notes here".into())
             }),
@@ -143,8 +590,8 @@ mod tests {
         let result = Entry::parse("9 1045 GHI", &DATE);
         assert_eq!(
             Some(Entry {
-                from: datetime(9, 0),
-                until: datetime(10, 45),
+                date: *DATE,
+                time: interval(datetime(9, 0), datetime(10, 45)),
                 project: "GHI".into(),
                 notes: None
             }),
@@ -157,8 +604,8 @@ mod tests {
         let result = Entry::parse("9-1045:JKL", &DATE);
         assert_eq!(
             Some(Entry {
-                from: datetime(9, 0),
-                until: datetime(10, 45),
+                date: *DATE,
+                time: interval(datetime(9, 0), datetime(10, 45)),
                 project: "JKL".into(),
                 notes: None
             }),
@@ -171,8 +618,8 @@ mod tests {
         let result = Entry::parse("   09:00  \t- \t10:45  :MNO", &DATE);
         assert_eq!(
             Some(Entry {
-                from: datetime(9, 0),
-                until: datetime(10, 45),
+                date: *DATE,
+                time: interval(datetime(9, 0), datetime(10, 45)),
                 project: "MNO".into(),
                 notes: None
             }),
@@ -185,8 +632,8 @@ mod tests {
         let result = Entry::parse("0900 1045 \"Some project\" with notes", &DATE);
         assert_eq!(
             Some(Entry {
-                from: datetime(9, 0),
-                until: datetime(10, 45),
+                date: *DATE,
+                time: interval(datetime(9, 0), datetime(10, 45)),
                 project: "Some project".into(),
                 notes: Some("with notes".into())
             }),
@@ -199,8 +646,8 @@ mod tests {
         let result = Entry::parse("9 10 \"A\"", &DATE);
         assert_eq!(
             Some(Entry {
-                from: datetime(9, 0),
-                until: datetime(10, 0),
+                date: *DATE,
+                time: interval(datetime(9, 0), datetime(10, 0)),
                 project: "A".into(),
                 notes: None
             }),
@@ -208,6 +655,126 @@ mod tests {
         );
     }
 
+    #[test]
+    fn twelve_hour_clock() {
+        let result = Entry::parse("9am - 1:30pm ABC", &DATE);
+        assert_eq!(
+            Some(interval(datetime(9, 0), datetime(13, 30))),
+            result.map(|e| e.time)
+        );
+        let result = Entry::parse("12 a.m. - 12:15 A.M.: ABC", &DATE);
+        assert_eq!(
+            Some(interval(datetime(0, 0), datetime(0, 15))),
+            result.map(|e| e.time)
+        );
+        let result = Entry::parse("11:30am 12pm ABC", &DATE);
+        assert_eq!(
+            Some(interval(datetime(11, 30), datetime(12, 0))),
+            result.map(|e| e.time)
+        );
+    }
+
+    #[test]
+    fn twelve_hour_clock_borrowed_meridiem() {
+        let result = Entry::parse("1-3pm ABC", &DATE);
+        assert_eq!(
+            Some(interval(datetime(13, 0), datetime(15, 0))),
+            result.map(|e| e.time)
+        );
+        let result = Entry::parse("10-11am ABC", &DATE);
+        assert_eq!(
+            Some(interval(datetime(10, 0), datetime(11, 0))),
+            result.map(|e| e.time)
+        );
+        let result = Entry::parse("11-1pm ABC", &DATE);
+        assert_eq!(
+            Some(interval(datetime(11, 0), datetime(13, 0))),
+            result.map(|e| e.time)
+        );
+    }
+
+    #[test]
+    fn twelve_hour_clock_invalid() {
+        assert_eq!(None, Entry::parse("13pm - 14pm ABC", &DATE));
+        assert_eq!(None, Entry::parse("0am - 1am ABC", &DATE));
+    }
+
+    #[test]
+    fn project_starting_with_meridiem() {
+        let result = Entry::parse("9-10 PMO", &DATE).unwrap();
+        assert_eq!(interval(datetime(9, 0), datetime(10, 0)), result.time);
+        assert_eq!("PMO", result.project);
+    }
+
+    #[test]
+    fn start_and_duration() {
+        let result = Entry::parse("9:00 +45m ABC notes", &DATE);
+        assert_eq!(
+            Some(Entry {
+                date: *DATE,
+                time: interval(datetime(9, 0), datetime(9, 45)),
+                project: "ABC".into(),
+                notes: Some("notes".into())
+            }),
+            result
+        );
+        let result = Entry::parse("9am + 1h30: ABC", &DATE);
+        assert_eq!(
+            Some(interval(datetime(9, 0), datetime(10, 30))),
+            result.map(|e| e.time)
+        );
+    }
+
+    #[test]
+    fn duration_only() {
+        let expected = Some(Entry {
+            date: *DATE,
+            time: Time::Duration(chrono::TimeDelta::minutes(90)),
+            project: "ABC".into(),
+            notes: None,
+        });
+        assert_eq!(expected, Entry::parse("1h30 ABC", &DATE));
+        assert_eq!(expected, Entry::parse("90m ABC", &DATE));
+        assert_eq!(expected, Entry::parse("1.5h: ABC", &DATE));
+        assert_eq!(expected, Entry::parse("1,5 hours ABC", &DATE));
+        assert_eq!(expected, Entry::parse("1h 30min ABC", &DATE));
+        assert_eq!(expected, Entry::parse("90 minutes ABC", &DATE));
+    }
+
+    #[test]
+    fn duration_only_zero() {
+        assert_eq!(None, Entry::parse("0h ABC", &DATE));
+        assert_eq!(None, Entry::parse("0m ABC", &DATE));
+    }
+
+    #[test]
+    fn all_day() {
+        let expected = Entry {
+            date: *DATE,
+            time: Time::AllDay,
+            project: "LEAVE".into(),
+            notes: Some("dentist".into()),
+        };
+        assert_eq!(
+            Some(&expected),
+            Entry::parse("all day: LEAVE dentist", &DATE).as_ref()
+        );
+        assert_eq!(
+            Some(&expected),
+            Entry::parse("All-day LEAVE dentist", &DATE).as_ref()
+        );
+        assert_eq!(chrono::TimeDelta::zero(), expected.duration());
+        assert_eq!("all day: LEAVE dentist", expected.to_markdown());
+    }
+
+    #[test]
+    fn duration_only_has_no_clock_times() {
+        let result = Entry::parse("45m ABC", &DATE).unwrap();
+        assert_eq!(None, result.from());
+        assert_eq!(None, result.until());
+        assert_eq!(chrono::TimeDelta::minutes(45), result.duration());
+    }
+
     #[test]
     fn invalid_from_time() {
         let result = Entry::parse("9:99 - 11:00: ABC", &DATE);
@@ -280,6 +847,13 @@ mod tests {
         assert!(a <= b);
     }
 
+    #[test]
+    fn cmp_duration_after_interval() {
+        let a = Entry::parse("9-10:ABC", &DATE).unwrap();
+        let b = Entry::parse("1h:ABC", &DATE).unwrap();
+        assert!(a < b);
+    }
+
     #[test]
     fn eq() {
         let a = Entry::parse("9-10:ABC", &DATE).unwrap();
@@ -306,4 +880,102 @@ mod tests {
         let result = a.to_string();
         assert_eq!("2024-02-13 | 09:00 - 10:00: ABC - some notes", result);
     }
+
+    #[test]
+    fn display_duration() {
+        let a = Entry::parse("90m ABC", &DATE).unwrap();
+        let result = a.to_string();
+        assert_eq!("2024-02-13 | 1h30: ABC", result);
+    }
+
+    #[test]
+    fn tags() {
+        let entry = Entry::parse("9-10 ABC fixed #bug-123, #review pending (not#tag)", &DATE);
+        assert_eq!(vec!["bug-123", "review"], entry.unwrap().tags());
+        let entry = Entry::parse("9-10 ABC", &DATE);
+        assert!(entry.unwrap().tags().is_empty());
+    }
+
+    #[test]
+    fn to_markdown() {
+        for (input, expected) in [
+            ("9-10:ABC", "09:00 - 10:00: ABC"),
+            ("9-10:ABC some notes", "09:00 - 10:00: ABC some notes"),
+            ("9-10 \"Some project\"", "09:00 - 10:00: \"Some project\""),
+            ("9-10 \"AB\"", "09:00 - 10:00: \"AB\""),
+            ("1h30 ABC", "1h30: ABC"),
+            ("45m ABC", "45m: ABC"),
+            ("23:00 +2h ABC", "23:00 +2h: ABC"),
+        ] {
+            let entry = Entry::parse(input, &DATE).unwrap();
+            let result = entry.to_markdown();
+            assert_eq!(expected, result);
+            assert_eq!(Some(entry), Entry::parse(&result, &DATE));
+        }
+    }
+
+    #[test]
+    fn new() {
+        let entry = Entry::new(*DATE, interval(datetime(9, 0), datetime(10, 0)), "ABC");
+        assert_eq!(Entry::parse("9-10 ABC", &DATE), entry.ok());
+        let entry = Entry::new(*DATE, interval(datetime(10, 0), datetime(9, 0)), "ABC");
+        assert!(matches!(entry, Err(Error::Entry(_))));
+        let entry = Entry::new(*DATE, Time::AllDay, " ");
+        assert!(matches!(entry, Err(Error::Entry(_))));
+        let entry = Entry::new(*DATE, Time::Duration(chrono::TimeDelta::days(1000)), "ABC");
+        assert!(matches!(entry, Err(Error::Entry(_))));
+    }
+
+    #[test]
+    fn builder() {
+        let nine = chrono::NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+        let entry = Entry::builder("ABC")
+            .on(*DATE)
+            .from(nine)
+            .lasting(chrono::TimeDelta::minutes(45))
+            .notes("fixed #ci")
+            .build()
+            .unwrap();
+        assert_eq!(
+            Entry::parse("9:00 +45m ABC fixed #ci", &DATE),
+            Some(entry.clone())
+        );
+        assert_eq!(Some("fixed #ci"), entry.notes());
+        assert_eq!(vec!["ci"], entry.tags());
+        let entry = Entry::builder("LEAVE").on(*DATE).all_day().build().unwrap();
+        assert_eq!(Time::AllDay, *entry.time());
+        assert!(Entry::builder("ABC")
+            .from(nine)
+            .until(nine)
+            .build()
+            .is_err());
+        assert!(Entry::builder("ABC").on(*DATE).from(nine).build().is_err());
+        assert!(Entry::builder("ABC")
+            .on(*DATE)
+            .all_day()
+            .lasting(chrono::TimeDelta::hours(1))
+            .build()
+            .is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let entry = Entry::parse("9-10:30 ABC fixed #ci", &DATE).unwrap();
+        let json = serde_json::to_string(&entry).unwrap();
+        assert_eq!(
+            r#"{"date":"2024-02-13","time":"interval","from":"2024-02-13T09:00:00","until":"2024-02-13T10:30:00","project":"ABC","notes":"fixed #ci"}"#,
+            json
+        );
+        assert_eq!(entry, serde_json::from_str(&json).unwrap());
+        let entry = Entry::parse("1h30 ABC", &DATE).unwrap();
+        let json = serde_json::to_string(&entry).unwrap();
+        assert_eq!(
+            r#"{"date":"2024-02-13","time":"duration","minutes":90,"project":"ABC"}"#,
+            json
+        );
+        assert_eq!(entry, serde_json::from_str(&json).unwrap());
+        let json = r#"{"date":"2024-02-13","time":"duration","minutes":-90,"project":"ABC"}"#;
+        assert!(serde_json::from_str::<Entry>(json).is_err());
+    }
 }
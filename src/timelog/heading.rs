@@ -0,0 +1,293 @@
+use super::{Context, Locale};
+use regex::Regex;
+use std::sync::LazyLock;
+
+static DATE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b\d{4}-\d{2}-\d{2}\b").unwrap());
+static TOKEN_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\p{L}+|\d+").unwrap());
+static ORDINALS: [&str; 6] = ["st", "nd", "rd", "th", "e", "er"];
+
+/// Ways of writing the date in a heading, on top of `yyyy-mm-dd` which is always recognised.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HeadingFormats {
+    /// `chrono` format strings, like `%d-%m-%Y` for `13-02-2024`.
+    pub formats: Vec<String>,
+    /// Languages of month and weekday names, as in `Tue 13 Feb 2024` or `13 februari 2024`.
+    pub locales: Vec<Locale>,
+}
+
+impl Default for HeadingFormats {
+    fn default() -> Self {
+        Self {
+            formats: vec![],
+            locales: vec![Locale::En],
+        }
+    }
+}
+
+/// The outcome of looking for a date in a heading.
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct HeadingDate {
+    pub date: Option<chrono::NaiveDate>,
+    pub warning: Option<String>,
+}
+
+impl HeadingDate {
+    fn warning(message: String) -> Self {
+        Self {
+            date: None,
+            warning: Some(message),
+        }
+    }
+}
+
+struct Token<'a> {
+    text: &'a str,
+    start: usize,
+}
+
+impl HeadingFormats {
+    /// Finds the date a heading refers to. Dates are spelled out (`2024-02-13`, `13 Feb 2024`) or
+    /// given as a weekday within the context's date range (`Tuesday`).
+    pub(super) fn parse(&self, s: &str, context: &Context) -> HeadingDate {
+        let tokens = TOKEN_RE
+            .find_iter(s)
+            .map(|m| Token {
+                text: m.as_str(),
+                start: m.start(),
+            })
+            .collect::<Vec<_>>();
+        let mut candidates = match DATE_RE.find(s) {
+            Some(m) => chrono::NaiveDate::parse_from_str(m.as_str(), "%Y-%m-%d")
+                .map(|date| vec![(date, m.start())])
+                .unwrap_or_default(),
+            None => self.candidates(s, &tokens),
+        };
+        candidates.sort();
+        candidates.dedup_by_key(|(date, _)| *date);
+        match candidates[..] {
+            [] => self.weekday_only(&tokens, context),
+            [(date, start)] => HeadingDate {
+                date: Some(date),
+                warning: self.check_weekday(&tokens, date, start),
+            },
+            _ => HeadingDate::warning(format!(
+                "ambiguous date, could be any of {}",
+                candidates
+                    .iter()
+                    .map(|(date, _)| date.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+
+    /// Collects every date found by the configured formats and locales, with its byte offset.
+    fn candidates(&self, s: &str, tokens: &[Token]) -> Vec<(chrono::NaiveDate, usize)> {
+        let mut candidates = vec![];
+        for format in self.formats.iter() {
+            for token in tokens.iter() {
+                let Ok((date, rest)) =
+                    chrono::NaiveDate::parse_and_remainder(&s[token.start..], format)
+                else {
+                    continue;
+                };
+                if !rest.starts_with(char::is_alphanumeric) {
+                    candidates.push((date, token.start));
+                }
+            }
+        }
+        for locale in self.locales.iter() {
+            for (index, token) in tokens.iter().enumerate() {
+                if let Some(date) = spelled_out(*locale, &tokens[index..]) {
+                    candidates.push((date, token.start));
+                }
+            }
+        }
+        candidates
+    }
+
+    fn weekday(&self, token: &Token) -> Vec<chrono::Weekday> {
+        let mut weekdays = self
+            .locales
+            .iter()
+            .filter_map(|locale| locale.weekday(token.text))
+            .collect::<Vec<_>>();
+        weekdays.dedup();
+        weekdays
+    }
+
+    fn weekday_only(&self, tokens: &[Token], context: &Context) -> HeadingDate {
+        let Some(first) = tokens.first() else {
+            return HeadingDate::default();
+        };
+        match self.weekday(first)[..] {
+            [] => HeadingDate::default(),
+            [weekday] => match context.resolve_weekday(weekday) {
+                Ok(date) => HeadingDate {
+                    date: Some(date),
+                    warning: None,
+                },
+                Err(message) => HeadingDate::warning(message),
+            },
+            _ => HeadingDate::warning(format!(
+                "ambiguous weekday \"{}\" in the configured locales",
+                first.text
+            )),
+        }
+    }
+
+    /// Warns when a heading starts with a weekday that doesn't match the date following it.
+    fn check_weekday(
+        &self,
+        tokens: &[Token],
+        date: chrono::NaiveDate,
+        start: usize,
+    ) -> Option<String> {
+        let first = tokens.first().filter(|token| token.start < start)?;
+        let weekdays = self.weekday(first);
+        let actual = chrono::Datelike::weekday(&date);
+        (!weekdays.is_empty() && !weekdays.contains(&actual))
+            .then(|| format!("{} is a {}, not a {}", date, actual, first.text))
+    }
+}
+
+/// Recognises `13 Feb 2024`, `13th of February 2024` and `February 13, 2024` at the start of the
+/// given tokens.
+fn spelled_out(locale: Locale, tokens: &[Token]) -> Option<chrono::NaiveDate> {
+    let words = tokens
+        .iter()
+        .map(|token| token.text)
+        .filter(|text| !ORDINALS.contains(text) && *text != "of")
+        .take(3)
+        .collect::<Vec<_>>();
+    let [first, second, year] = words[..] else {
+        return None;
+    };
+    if year.len() != 4 {
+        return None;
+    }
+    let year = year.parse::<i32>().ok()?;
+    let (day, month) = match (first.parse::<u32>(), second.parse::<u32>()) {
+        (Ok(day), Err(_)) if first.len() <= 2 => (day, locale.month(second)?),
+        (Err(_), Ok(day)) if second.len() <= 2 => (day, locale.month(first)?),
+        _ => return None,
+    };
+    chrono::NaiveDate::from_ymd_opt(year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::DateRange;
+
+    fn date(y: i32, m: u32, d: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn formats(formats: &[&str], locales: &[Locale]) -> HeadingFormats {
+        HeadingFormats {
+            formats: formats.iter().map(|f| f.to_string()).collect(),
+            locales: locales.to_vec(),
+        }
+    }
+
+    fn parse(formats: &HeadingFormats, s: &str) -> HeadingDate {
+        formats.parse(s, &Context::default())
+    }
+
+    #[test]
+    fn iso() {
+        let result = parse(&HeadingFormats::default(), "2024-02-13 (a tuesday)");
+        assert_eq!(Some(date(2024, 2, 13)), result.date);
+        assert_eq!(None, result.warning);
+    }
+
+    #[test]
+    fn no_date() {
+        let result = parse(&HeadingFormats::default(), "Meeting notes");
+        assert_eq!(HeadingDate::default(), result);
+    }
+
+    #[test]
+    fn configured_formats() {
+        let formats = formats(&["%d-%m-%Y", "%Y/%m/%d"], &[]);
+        assert_eq!(Some(date(2024, 2, 13)), parse(&formats, "13-02-2024").date);
+        assert_eq!(
+            Some(date(2024, 2, 13)),
+            parse(&formats, "Day 2024/02/13").date
+        );
+        assert_eq!(None, parse(&formats, "113-02-2024").date);
+    }
+
+    #[test]
+    fn spelled_out_english() {
+        let formats = HeadingFormats::default();
+        assert_eq!(
+            Some(date(2024, 2, 13)),
+            parse(&formats, "Tue 13 Feb 2024").date
+        );
+        assert_eq!(
+            Some(date(2024, 2, 13)),
+            parse(&formats, "February 13th, 2024").date
+        );
+        assert_eq!(
+            Some(date(2024, 2, 13)),
+            parse(&formats, "13th of February 2024").date
+        );
+    }
+
+    #[test]
+    fn spelled_out_other_locales() {
+        let formats = formats(&[], &[Locale::Nl, Locale::De]);
+        assert_eq!(
+            Some(date(2024, 2, 13)),
+            parse(&formats, "13 februari 2024").date
+        );
+        assert_eq!(
+            Some(date(2024, 3, 1)),
+            parse(&formats, "Fr, 1. März 2024").date
+        );
+        assert_eq!(None, parse(&formats, "13 Feburary 2024").date);
+    }
+
+    #[test]
+    fn ambiguous_formats() {
+        let formats = formats(&["%d-%m-%Y", "%m-%d-%Y"], &[]);
+        let result = parse(&formats, "01-02-2024");
+        assert_eq!(None, result.date);
+        assert_eq!(
+            Some("ambiguous date, could be any of 2024-01-02, 2024-02-01".into()),
+            result.warning
+        );
+        assert_eq!(Some(date(2024, 2, 13)), parse(&formats, "13-02-2024").date);
+    }
+
+    #[test]
+    fn mismatched_weekday() {
+        let result = parse(&HeadingFormats::default(), "Wed 13 Feb 2024");
+        assert_eq!(Some(date(2024, 2, 13)), result.date);
+        assert_eq!(
+            Some("2024-02-13 is a Tue, not a Wed".into()),
+            result.warning
+        );
+    }
+
+    #[test]
+    fn weekday_only() {
+        let context = Context {
+            range: Some(DateRange(date(2024, 2, 12), date(2024, 2, 18))),
+            ..Default::default()
+        };
+        let formats = formats(&[], &[Locale::En, Locale::Nl]);
+        assert_eq!(
+            Some(date(2024, 2, 13)),
+            formats.parse("Dinsdag", &context).date
+        );
+        assert_eq!(
+            Some(date(2024, 2, 15)),
+            formats.parse("Thu.", &context).date
+        );
+        assert_eq!(None, formats.parse("Sunny days", &context).date);
+    }
+}
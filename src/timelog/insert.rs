@@ -0,0 +1,321 @@
+use super::{parser, Context, Entry, Log};
+use crate::Error;
+use indexmap::map::IndexMap;
+use markdown::mdast;
+use std::collections::BTreeMap;
+
+struct Heading {
+    depth: u8,
+    line: usize,
+    date: Option<chrono::NaiveDate>,
+}
+
+struct Block {
+    start: usize,
+    end: usize,
+    list: bool,
+}
+
+/// Adds entries to a markdown log, below the heading for their date. Entries are appended to the
+/// last list in that section, and headings are added in date order for dates that don't have one
+/// yet. Entries equal to ones already in the log are left out.
+///
+/// Returns the new content, and the entries that were added to it.
+pub fn insert(
+    input: &str,
+    entries: &[Entry],
+    context: &Context,
+) -> Result<(String, Vec<Entry>), Error> {
+    let Log(existing) = Log::parse(input, context)?;
+    let mut entries = entries
+        .iter()
+        .filter(|entry| !existing.contains(entry))
+        .cloned()
+        .collect::<Vec<_>>();
+    entries.sort();
+    entries.dedup();
+    let mut by_date: IndexMap<chrono::NaiveDate, Vec<Entry>> = IndexMap::new();
+    for entry in entries.iter() {
+        by_date.entry(entry.date).or_default().push(entry.clone());
+    }
+
+    let lines = input.lines().collect::<Vec<_>>();
+    let (headings, blocks) = outline(input, context)?;
+    let heading_depth = headings
+        .iter()
+        .find(|heading| heading.date.is_some())
+        .map_or(2, |heading| heading.depth);
+    // Text to insert before a (0-based) line index, where `lines.len()` is the end of the input.
+    let mut inserts: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for (date, entries) in by_date.iter() {
+        let section = headings
+            .iter()
+            .enumerate()
+            .find(|(_, heading)| heading.date.as_ref() == Some(date));
+        let (index, text) = match section {
+            Some((i, heading)) => {
+                let end = headings[i + 1..]
+                    .iter()
+                    .find(|next| next.depth <= heading.depth)
+                    .map_or(lines.len() + 1, |next| next.line);
+                let blocks = blocks
+                    .iter()
+                    .filter(|block| block.start > heading.line && block.end < end)
+                    .collect::<Vec<_>>();
+                match blocks.iter().rev().find(|block| block.list) {
+                    Some(list) => (list.end, list_items(entries, lines[list.start - 1])),
+                    None => {
+                        let after = blocks.last().map_or(heading.line, |block| block.end);
+                        let mut text = format!("\n{}", list_items(entries, "-"));
+                        if lines.get(after).is_some_and(|line| !line.trim().is_empty()) {
+                            text.push('\n');
+                        }
+                        (after, text)
+                    }
+                }
+            }
+            None => {
+                let section = format!(
+                    "{} {}\n\n{}",
+                    "#".repeat(heading_depth.into()),
+                    date,
+                    list_items(entries, "-")
+                );
+                let later = headings
+                    .iter()
+                    .find(|heading| heading.date.is_some_and(|d| d > *date));
+                match later {
+                    Some(heading) => (heading.line - 1, format!("{}\n", section)),
+                    None if inserts.contains_key(&lines.len())
+                        || lines.last().is_some_and(|line| !line.trim().is_empty()) =>
+                    {
+                        (lines.len(), format!("\n{}", section))
+                    }
+                    None => (lines.len(), section),
+                }
+            }
+        };
+        inserts.entry(index).or_default().push(text);
+    }
+
+    let mut output = String::new();
+    for index in 0..=lines.len() {
+        for text in inserts.get(&index).into_iter().flatten() {
+            output.push_str(text);
+        }
+        if let Some(line) = lines.get(index) {
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+    Ok((output, entries))
+}
+
+/// Collects the top-level headings and other blocks of a markdown log, with 1-based line numbers.
+fn outline(input: &str, context: &Context) -> Result<(Vec<Heading>, Vec<Block>), Error> {
+    let ast = parser::parse_md(input)?;
+    let mut headings = vec![];
+    let mut blocks = vec![];
+    for node in ast.children().into_iter().flatten() {
+        let Some(position) = node.position() else {
+            continue;
+        };
+        if let mdast::Node::Heading(heading) = node {
+            let date = context.headings.parse(&node.to_string(), context).date;
+            headings.push(Heading {
+                depth: heading.depth,
+                line: position.start.line,
+                date,
+            });
+        } else {
+            // Blocks followed by a blank line may end at the start of that line.
+            let end = match position.end.column {
+                1 => position.end.line - 1,
+                _ => position.end.line,
+            };
+            blocks.push(Block {
+                start: position.start.line,
+                end,
+                list: matches!(node, mdast::Node::List(_)),
+            });
+        }
+    }
+    Ok((headings, blocks))
+}
+
+/// Formats entries as list items, copying the indentation and bullet from an existing item.
+fn list_items(entries: &[Entry], example: &str) -> String {
+    let indent = &example[..example.len() - example.trim_start().len()];
+    let bullet = example
+        .trim_start()
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '-' | '*' | '+'))
+        .unwrap_or('-');
+    entries
+        .iter()
+        .map(|entry| format!("{}{} {}\n", indent, bullet, entry.to_markdown()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn entries(entries: &[(chrono::NaiveDate, &str)]) -> Vec<Entry> {
+        entries
+            .iter()
+            .map(|(date, s)| Entry::parse(s, date).unwrap())
+            .collect()
+    }
+
+    fn insert(input: &str, entries: &[Entry]) -> (String, Vec<Entry>) {
+        super::insert(input, entries, &Context::default()).unwrap()
+    }
+
+    #[test]
+    fn empty_input() {
+        let entries = entries(&[
+            (date(2024, 2, 14), "9-10 ABC"),
+            (date(2024, 2, 13), "9-10 DEF"),
+        ]);
+        let (result, inserted) = insert("", &entries);
+        let expected = indoc::indoc! {"
+            ## 2024-02-13
+
+            - 09:00 - 10:00: DEF
+
+            ## 2024-02-14
+
+            - 09:00 - 10:00: ABC
+        "};
+        assert_eq!(expected, result);
+        assert_eq!(2, inserted.len());
+    }
+
+    #[test]
+    fn append_to_list() {
+        let input = indoc::indoc! {"
+            # 2024-W07
+
+            ## 2024-02-13
+
+            * 09:00 - 10:00: ABC
+              * some notes
+
+            Some more notes.
+
+            ## 2024-02-14
+        "};
+        let entries = entries(&[(date(2024, 2, 13), "10-11 DEF")]);
+        let (result, _) = insert(input, &entries);
+        let expected = indoc::indoc! {"
+            # 2024-W07
+
+            ## 2024-02-13
+
+            * 09:00 - 10:00: ABC
+              * some notes
+            * 10:00 - 11:00: DEF
+
+            Some more notes.
+
+            ## 2024-02-14
+        "};
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn add_list_to_section() {
+        let input = indoc::indoc! {"
+            ## 2024-02-13
+            Nothing logged yet.
+            ## 2024-02-14
+        "};
+        let entries = entries(&[(date(2024, 2, 13), "10-11 DEF")]);
+        let (result, _) = insert(input, &entries);
+        let expected = indoc::indoc! {"
+            ## 2024-02-13
+            Nothing logged yet.
+
+            - 10:00 - 11:00: DEF
+
+            ## 2024-02-14
+        "};
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn add_heading_in_date_order() {
+        let input = indoc::indoc! {"
+            # 2024-W07
+
+            ### 2024-02-12
+
+            - 09:00 - 10:00: ABC
+
+            ### 2024-02-14
+
+            - 09:00 - 10:00: ABC
+        "};
+        let entries = entries(&[
+            (date(2024, 2, 13), "10-11 DEF"),
+            (date(2024, 2, 15), "10-11 DEF"),
+        ]);
+        let (result, _) = insert(input, &entries);
+        let expected = indoc::indoc! {"
+            # 2024-W07
+
+            ### 2024-02-12
+
+            - 09:00 - 10:00: ABC
+
+            ### 2024-02-13
+
+            - 10:00 - 11:00: DEF
+
+            ### 2024-02-14
+
+            - 09:00 - 10:00: ABC
+
+            ### 2024-02-15
+
+            - 10:00 - 11:00: DEF
+        "};
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn skip_existing_entries() {
+        let input = indoc::indoc! {"
+            ## 2024-02-13
+            - 9-10 ABC
+        "};
+        let entries = entries(&[
+            (date(2024, 2, 13), "09:00 - 10:00 ABC notes don't matter"),
+            (date(2024, 2, 13), "10-11 DEF"),
+            (date(2024, 2, 13), "10-11 DEF"),
+        ]);
+        let (result, inserted) = insert(input, &entries);
+        let expected = indoc::indoc! {"
+            ## 2024-02-13
+            - 9-10 ABC
+            - 10:00 - 11:00: DEF
+        "};
+        assert_eq!(expected, result);
+        assert_eq!(1, inserted.len());
+    }
+
+    #[test]
+    fn nothing_to_insert() {
+        let input = "## 2024-02-13\n- 9-10 ABC\n";
+        let entries = entries(&[(date(2024, 2, 13), "9-10 ABC")]);
+        let (result, inserted) = insert(input, &entries);
+        assert_eq!(input, result);
+        assert!(inserted.is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+use std::{fmt, str::FromStr};
+
+/// Languages in which month and weekday names in headings are recognised.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub enum Locale {
+    En,
+    Nl,
+    De,
+    Fr,
+    Es,
+}
+
+type Names = &'static [&'static str];
+
+impl Locale {
+    pub const ALL: [Self; 5] = [Self::En, Self::Nl, Self::De, Self::Fr, Self::Es];
+
+    /// Month names and their abbreviations, January first.
+    fn months(self) -> [Names; 12] {
+        match self {
+            Self::En => [
+                &["january", "jan"],
+                &["february", "feb"],
+                &["march", "mar"],
+                &["april", "apr"],
+                &["may"],
+                &["june", "jun"],
+                &["july", "jul"],
+                &["august", "aug"],
+                &["september", "sep", "sept"],
+                &["october", "oct"],
+                &["november", "nov"],
+                &["december", "dec"],
+            ],
+            Self::Nl => [
+                &["januari", "jan"],
+                &["februari", "feb"],
+                &["maart", "mrt", "maa"],
+                &["april", "apr"],
+                &["mei"],
+                &["juni", "jun"],
+                &["juli", "jul"],
+                &["augustus", "aug"],
+                &["september", "sep", "sept"],
+                &["oktober", "okt"],
+                &["november", "nov"],
+                &["december", "dec"],
+            ],
+            Self::De => [
+                &["januar", "jan", "jänner"],
+                &["februar", "feb"],
+                &["märz", "mär", "maerz"],
+                &["april", "apr"],
+                &["mai"],
+                &["juni", "jun"],
+                &["juli", "jul"],
+                &["august", "aug"],
+                &["september", "sep", "sept"],
+                &["oktober", "okt"],
+                &["november", "nov"],
+                &["dezember", "dez"],
+            ],
+            Self::Fr => [
+                &["janvier", "janv"],
+                &["février", "févr", "fevrier"],
+                &["mars"],
+                &["avril", "avr"],
+                &["mai"],
+                &["juin"],
+                &["juillet", "juil"],
+                &["août", "aout"],
+                &["septembre", "sept"],
+                &["octobre", "oct"],
+                &["novembre", "nov"],
+                &["décembre", "déc", "decembre"],
+            ],
+            Self::Es => [
+                &["enero", "ene"],
+                &["febrero", "feb"],
+                &["marzo", "mar"],
+                &["abril", "abr"],
+                &["mayo", "may"],
+                &["junio", "jun"],
+                &["julio", "jul"],
+                &["agosto", "ago"],
+                &["septiembre", "setiembre", "sep", "sept"],
+                &["octubre", "oct"],
+                &["noviembre", "nov"],
+                &["diciembre", "dic"],
+            ],
+        }
+    }
+
+    /// Weekday names and their abbreviations, Monday first.
+    fn weekdays(self) -> [Names; 7] {
+        match self {
+            Self::En => [
+                &["monday", "mon"],
+                &["tuesday", "tue", "tues"],
+                &["wednesday", "wed"],
+                &["thursday", "thu", "thurs"],
+                &["friday", "fri"],
+                &["saturday", "sat"],
+                &["sunday", "sun"],
+            ],
+            Self::Nl => [
+                &["maandag", "ma"],
+                &["dinsdag", "di"],
+                &["woensdag", "wo"],
+                &["donderdag", "do"],
+                &["vrijdag", "vr"],
+                &["zaterdag", "za"],
+                &["zondag", "zo"],
+            ],
+            Self::De => [
+                &["montag", "mo"],
+                &["dienstag", "di"],
+                &["mittwoch", "mi"],
+                &["donnerstag", "do"],
+                &["freitag", "fr"],
+                &["samstag", "sa", "sonnabend"],
+                &["sonntag", "so"],
+            ],
+            Self::Fr => [
+                &["lundi", "lun"],
+                &["mardi", "mar"],
+                &["mercredi", "mer"],
+                &["jeudi", "jeu"],
+                &["vendredi", "ven"],
+                &["samedi", "sam"],
+                &["dimanche", "dim"],
+            ],
+            Self::Es => [
+                &["lunes", "lun"],
+                &["martes", "mar"],
+                &["miércoles", "mié", "miercoles", "mie"],
+                &["jueves", "jue"],
+                &["viernes", "vie"],
+                &["sábado", "sáb", "sabado", "sab"],
+                &["domingo", "dom"],
+            ],
+        }
+    }
+
+    /// Looks up a month by (case-insensitive) name or abbreviation, returning 1 for January.
+    pub fn month(self, word: &str) -> Option<u32> {
+        let word = word.to_lowercase();
+        let index = self
+            .months()
+            .iter()
+            .position(|names| names.contains(&word.as_str()))?;
+        Some(index as u32 + 1)
+    }
+
+    /// Looks up a weekday by (case-insensitive) name or abbreviation.
+    pub fn weekday(self, word: &str) -> Option<chrono::Weekday> {
+        let word = word.to_lowercase();
+        let index = self
+            .weekdays()
+            .iter()
+            .position(|names| names.contains(&word.as_str()))?;
+        chrono::Weekday::try_from(index as u8).ok()
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    /// Parses a language code like `nl`, optionally with a region (`nl_BE`, `en-GB`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let language = s.split(['_', '-']).next().unwrap_or_default();
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.to_string().eq_ignore_ascii_case(language))
+            .ok_or_else(|| format!("unsupported locale \"{}\"", s))
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let code = match self {
+            Self::En => "en",
+            Self::Nl => "nl",
+            Self::De => "de",
+            Self::Fr => "fr",
+            Self::Es => "es",
+        };
+        write!(f, "{}", code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn month() {
+        assert_eq!(Some(2), Locale::En.month("Feb"));
+        assert_eq!(Some(2), Locale::Nl.month("februari"));
+        assert_eq!(Some(3), Locale::De.month("März"));
+        assert_eq!(Some(8), Locale::Fr.month("août"));
+        assert_eq!(None, Locale::En.month("februari"));
+    }
+
+    #[test]
+    fn weekday() {
+        assert_eq!(Some(chrono::Weekday::Tue), Locale::En.weekday("TUE"));
+        assert_eq!(Some(chrono::Weekday::Thu), Locale::Nl.weekday("do"));
+        assert_eq!(Some(chrono::Weekday::Wed), Locale::Es.weekday("miércoles"));
+        assert_eq!(None, Locale::En.weekday("sunny"));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Ok(Locale::Nl), "nl".parse());
+        assert_eq!(Ok(Locale::En), "en_GB".parse());
+        assert_eq!(Ok(Locale::De), "DE-at".parse());
+        assert!("xx".parse::<Locale>().is_err());
+    }
+}
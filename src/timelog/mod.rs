@@ -1,44 +1,124 @@
+#[cfg(feature = "cache")]
+mod cache;
+mod context;
 mod entry;
+mod heading;
+mod insert;
+mod locale;
+mod parser;
+mod scanner;
 
-pub use entry::Entry;
+use crate::utils::DateRange;
+use crate::Error;
+#[cfg(feature = "cache")]
+pub use cache::Cache;
+pub use context::{Context, Reader};
+pub use entry::{Entry, EntryBuilder, Time};
+pub use heading::HeadingFormats;
 use indexmap::map::IndexMap;
-use markdown::mdast;
-use regex::Regex;
-use std::sync::LazyLock;
+pub use insert::insert;
+pub use locale::Locale;
+use std::fmt;
 
-static DATE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b\d{4}-\d{2}-\d{2}\b").unwrap());
+/// Entries in order of date and time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Log(pub(crate) Vec<Entry>);
 
-#[derive(Debug, Default, PartialEq)]
-pub struct Log(pub Vec<Entry>);
+/// Something suspicious encountered while parsing, which didn't stop the parser.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Warning {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
 
 impl Log {
-    pub fn parse(input: &str) -> Self {
-        let ast = parse_md(input);
-        let nodes = ast.children().unwrap();
-        let mut date: Option<chrono::NaiveDate> = None;
-        let mut entries: Vec<Entry> = vec![];
-        for node in nodes.iter() {
-            if let mdast::Node::Heading(_) = node {
-                date = parse_heading(&node.to_string());
-                continue;
-            }
-            let mdast::Node::List(mdast::List { children, .. }) = node else {
-                continue;
-            };
-            let Some(ref date) = date else {
-                continue;
-            };
-            for list_item in children.iter() {
-                let Some(item_text) = list_item.children().unwrap().first() else {
-                    continue;
-                };
-                if let Some(entry) = Entry::parse(&item_text.to_string(), date) {
-                    entries.push(entry);
-                };
-            }
-        }
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(input: &str, context: &Context) -> Result<Self, Error> {
+        Ok(Self::parse_with_warnings(input, context)?.0)
+    }
+
+    pub fn parse_with_warnings(
+        input: &str,
+        context: &Context,
+    ) -> Result<(Self, Vec<Warning>), Error> {
+        let parser = parser::Parser::new(context);
+        let (mut entries, warnings) = match context.reader {
+            Reader::Markdown => parser.parse(input)?,
+            Reader::Lines => scanner::Scanner::new(parser, true).scan(input).finish(),
+            Reader::PlainLines => scanner::Scanner::new(parser, false).scan(input).finish(),
+        };
         entries.sort();
-        Self(entries)
+        Ok((Self(entries), warnings))
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.0
+    }
+
+    pub fn into_entries(self) -> Vec<Entry> {
+        self.0
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Entry> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The first and last date with entries.
+    pub fn range(&self) -> Option<DateRange> {
+        let first = self.0.iter().map(|entry| entry.date).min()?;
+        let last = self.0.iter().map(|entry| entry.date).max()?;
+        Some(DateRange(first, last))
+    }
+
+    /// The entries for which the predicate holds.
+    pub fn filter(&self, predicate: impl Fn(&Entry) -> bool) -> Self {
+        Self(
+            self.0
+                .iter()
+                .filter(|entry| predicate(entry))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// The entries of a project.
+    pub fn for_project(&self, project: &str) -> Self {
+        self.filter(|entry| entry.project == project)
+    }
+
+    /// The entries on a date.
+    pub fn on(&self, date: &chrono::NaiveDate) -> Self {
+        self.filter(|entry| &entry.date == date)
+    }
+
+    /// The entries on dates within the given range.
+    pub fn within(&self, range: &DateRange) -> Self {
+        let Self(entries) = self;
+        Self(
+            entries
+                .iter()
+                .filter(|entry| range.contains(&entry.date))
+                .cloned()
+                .collect(),
+        )
     }
 
     pub fn by_date(&self) -> IndexMap<chrono::NaiveDate, Self> {
@@ -48,10 +128,7 @@ impl Log {
         }
         let mut map: IndexMap<chrono::NaiveDate, Self> = IndexMap::new();
         for entry in entries {
-            map.entry(entry.from.date())
-                .or_default()
-                .0
-                .push(entry.clone())
+            map.entry(entry.date).or_default().0.push(entry.clone())
         }
         map
     }
@@ -78,14 +155,58 @@ impl Log {
     }
 }
 
-fn parse_heading(s: &str) -> Option<chrono::NaiveDate> {
-    let cap = DATE_RE.captures(s)?;
-    chrono::NaiveDate::parse_from_str(&cap[0], "%Y-%m-%d").ok()
+impl From<Vec<Entry>> for Log {
+    /// Puts the entries in order.
+    fn from(mut entries: Vec<Entry>) -> Self {
+        entries.sort();
+        Self(entries)
+    }
+}
+
+impl FromIterator<Entry> for Log {
+    fn from_iter<I: IntoIterator<Item = Entry>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl Extend<Entry> for Log {
+    fn extend<I: IntoIterator<Item = Entry>>(&mut self, iter: I) {
+        self.0.extend(iter);
+        self.0.sort();
+    }
+}
+
+/// Logs are serialized as a sequence of entries.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Log {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_seq(&self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Log {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        Vec::<Entry>::deserialize(d).map(Self::from)
+    }
+}
+
+impl IntoIterator for Log {
+    type Item = Entry;
+    type IntoIter = std::vec::IntoIter<Entry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
 }
 
-fn parse_md(input: &str) -> mdast::Node {
-    let opts = &markdown::ParseOptions::default();
-    markdown::to_mdast(input, opts).unwrap()
+impl<'a> IntoIterator for &'a Log {
+    type Item = &'a Entry;
+    type IntoIter = std::slice::Iter<'a, Entry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
 }
 
 #[cfg(test)]
@@ -103,19 +224,48 @@ mod tests {
         chrono::NaiveDateTime::new(date, time)
     }
 
+    fn parse(input: &str) -> Log {
+        Log::parse(input, &Context::default()).unwrap()
+    }
+
     fn map<T: Hash + Eq, U>(vec: Vec<(T, U)>) -> IndexMap<T, U> {
         vec.into_iter().collect::<IndexMap<T, U>>()
     }
 
     #[test]
     fn parse_empty_file() {
-        let log = Log::parse("");
+        let log = parse("");
         assert!(log.0.is_empty());
     }
 
+    #[test]
+    fn parse_leave() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13 (Vacation)
+            ## 2024-02-14
+            - all day: LEAVE
+        "});
+        let expected = vec![
+            Entry {
+                date: date(2024, 2, 13),
+                time: Time::AllDay,
+                project: "Vacation".to_owned(),
+                notes: None,
+            },
+            Entry {
+                date: date(2024, 2, 14),
+                time: Time::AllDay,
+                project: "LEAVE".to_owned(),
+                notes: None,
+            },
+        ];
+        assert_eq!(expected, log.0);
+        assert_eq!(chrono::TimeDelta::zero(), log.sum_duration());
+    }
+
     #[test]
     fn parse_unrelated_list() {
-        let log = Log::parse(indoc::indoc! {"
+        let log = parse(indoc::indoc! {"
             ## Just some list
             * A list
             * But no timelog entries
@@ -125,7 +275,7 @@ mod tests {
 
     #[test]
     fn parse_list_empty_items() {
-        let log = Log::parse(indoc::indoc! {"
+        let log = parse(indoc::indoc! {"
             ## 2024-02-13
             *
             *
@@ -135,7 +285,7 @@ mod tests {
 
     #[test]
     fn parse_no_list() {
-        let log = Log::parse(indoc::indoc! {"
+        let log = parse(indoc::indoc! {"
             ## 2024-02-13
             Just some notes, no list of timelog entries
         "});
@@ -144,7 +294,7 @@ mod tests {
 
     #[test]
     fn parse_list_without_entries() {
-        let log = Log::parse(indoc::indoc! {"
+        let log = parse(indoc::indoc! {"
             ## 2024-02-13
             * A list
             * But no timelog entries
@@ -154,21 +304,27 @@ mod tests {
 
     #[test]
     fn parse_simple_list() {
-        let log = Log::parse(indoc::indoc! {"
+        let log = parse(indoc::indoc! {"
             ## 2024-02-13
             * 9-10 ABC
             * 10-11 DEF
         "});
         let expected = Log(vec![
             Entry {
-                from: datetime(2024, 2, 13, 9, 0),
-                until: datetime(2024, 2, 13, 10, 0),
+                date: date(2024, 2, 13),
+                time: Time::Interval {
+                    from: datetime(2024, 2, 13, 9, 0),
+                    until: datetime(2024, 2, 13, 10, 0),
+                },
                 project: "ABC".into(),
                 notes: None,
             },
             Entry {
-                from: datetime(2024, 2, 13, 10, 0),
-                until: datetime(2024, 2, 13, 11, 0),
+                date: date(2024, 2, 13),
+                time: Time::Interval {
+                    from: datetime(2024, 2, 13, 10, 0),
+                    until: datetime(2024, 2, 13, 11, 0),
+                },
                 project: "DEF".into(),
                 notes: None,
             },
@@ -178,7 +334,7 @@ mod tests {
 
     #[test]
     fn parse_unrelated_paragraph_and_simple_list() {
-        let log = Log::parse(indoc::indoc! {"
+        let log = parse(indoc::indoc! {"
             ## 2024-02-13
             Some unrelated text, followed by list with entries:
             * 9-10 ABC
@@ -186,14 +342,20 @@ mod tests {
         "});
         let expected = Log(vec![
             Entry {
-                from: datetime(2024, 2, 13, 9, 0),
-                until: datetime(2024, 2, 13, 10, 0),
+                date: date(2024, 2, 13),
+                time: Time::Interval {
+                    from: datetime(2024, 2, 13, 9, 0),
+                    until: datetime(2024, 2, 13, 10, 0),
+                },
                 project: "ABC".into(),
                 notes: None,
             },
             Entry {
-                from: datetime(2024, 2, 13, 10, 0),
-                until: datetime(2024, 2, 13, 11, 0),
+                date: date(2024, 2, 13),
+                time: Time::Interval {
+                    from: datetime(2024, 2, 13, 10, 0),
+                    until: datetime(2024, 2, 13, 11, 0),
+                },
                 project: "DEF".into(),
                 notes: None,
             },
@@ -203,7 +365,7 @@ mod tests {
 
     #[test]
     fn parse_mixed_list() {
-        let log = Log::parse(indoc::indoc! {"
+        let log = parse(indoc::indoc! {"
             ## 2024-02-13
             Some unrelated text, followed by list with entries mixed with notes:
             * 9-10 ABC
@@ -213,14 +375,20 @@ mod tests {
         "});
         let expected = Log(vec![
             Entry {
-                from: datetime(2024, 2, 13, 9, 0),
-                until: datetime(2024, 2, 13, 10, 0),
+                date: date(2024, 2, 13),
+                time: Time::Interval {
+                    from: datetime(2024, 2, 13, 9, 0),
+                    until: datetime(2024, 2, 13, 10, 0),
+                },
                 project: "ABC".into(),
                 notes: None,
             },
             Entry {
-                from: datetime(2024, 2, 13, 10, 0),
-                until: datetime(2024, 2, 13, 11, 0),
+                date: date(2024, 2, 13),
+                time: Time::Interval {
+                    from: datetime(2024, 2, 13, 10, 0),
+                    until: datetime(2024, 2, 13, 11, 0),
+                },
                 project: "DEF".into(),
                 notes: None,
             },
@@ -230,7 +398,7 @@ mod tests {
 
     #[test]
     fn parse_multiple_dates_mixed_content() {
-        let log = Log::parse(indoc::indoc! {"
+        let log = parse(indoc::indoc! {"
             ## 2024-02-13
             Some unrelated text, followed by list with entries mixed with notes:
             * 9-10 ABC
@@ -242,20 +410,29 @@ mod tests {
         "});
         let expected = Log(vec![
             Entry {
-                from: datetime(2024, 2, 13, 9, 0),
-                until: datetime(2024, 2, 13, 10, 0),
+                date: date(2024, 2, 13),
+                time: Time::Interval {
+                    from: datetime(2024, 2, 13, 9, 0),
+                    until: datetime(2024, 2, 13, 10, 0),
+                },
                 project: "ABC".into(),
                 notes: None,
             },
             Entry {
-                from: datetime(2024, 2, 13, 10, 0),
-                until: datetime(2024, 2, 13, 11, 0),
+                date: date(2024, 2, 13),
+                time: Time::Interval {
+                    from: datetime(2024, 2, 13, 10, 0),
+                    until: datetime(2024, 2, 13, 11, 0),
+                },
                 project: "DEF".into(),
                 notes: None,
             },
             Entry {
-                from: datetime(2024, 2, 14, 9, 0),
-                until: datetime(2024, 2, 14, 10, 0),
+                date: date(2024, 2, 14),
+                time: Time::Interval {
+                    from: datetime(2024, 2, 14, 9, 0),
+                    until: datetime(2024, 2, 14, 10, 0),
+                },
                 project: "ABC".into(),
                 notes: None,
             },
@@ -265,7 +442,7 @@ mod tests {
 
     #[test]
     fn parse_multiple_dates_unordered_and_repeating() {
-        let log = Log::parse(indoc::indoc! {"
+        let log = parse(indoc::indoc! {"
             ## 2024-02-13
             * 9-10 ABC
             * 10-11 DEF
@@ -276,26 +453,38 @@ mod tests {
         "});
         let expected = Log(vec![
             Entry {
-                from: datetime(2024, 2, 13, 9, 0),
-                until: datetime(2024, 2, 13, 10, 0),
+                date: date(2024, 2, 13),
+                time: Time::Interval {
+                    from: datetime(2024, 2, 13, 9, 0),
+                    until: datetime(2024, 2, 13, 10, 0),
+                },
                 project: "ABC".into(),
                 notes: None,
             },
             Entry {
-                from: datetime(2024, 2, 13, 10, 0),
-                until: datetime(2024, 2, 13, 11, 0),
+                date: date(2024, 2, 13),
+                time: Time::Interval {
+                    from: datetime(2024, 2, 13, 10, 0),
+                    until: datetime(2024, 2, 13, 11, 0),
+                },
                 project: "DEF".into(),
                 notes: None,
             },
             Entry {
-                from: datetime(2024, 2, 13, 11, 0),
-                until: datetime(2024, 2, 13, 12, 0),
+                date: date(2024, 2, 13),
+                time: Time::Interval {
+                    from: datetime(2024, 2, 13, 11, 0),
+                    until: datetime(2024, 2, 13, 12, 0),
+                },
                 project: "GHI".into(),
                 notes: None,
             },
             Entry {
-                from: datetime(2024, 2, 14, 9, 0),
-                until: datetime(2024, 2, 14, 10, 0),
+                date: date(2024, 2, 14),
+                time: Time::Interval {
+                    from: datetime(2024, 2, 14, 9, 0),
+                    until: datetime(2024, 2, 14, 10, 0),
+                },
                 project: "ABC".into(),
                 notes: None,
             },
@@ -303,16 +492,179 @@ mod tests {
         assert_eq!(expected, log);
     }
 
+    #[test]
+    fn parse_nested_headings() {
+        let log = parse(indoc::indoc! {"
+            # 2024-W07
+            ## 2024-02-13
+            * 9-10 ABC
+            ### Meeting notes
+            * 10-11 DEF
+            ## Unrelated
+            * 11-12 GHI
+            ## 2024-02-14
+            * 9-10 ABC
+        "});
+        let expected = Log(vec![
+            Entry::parse("9-10 ABC", &date(2024, 2, 13)).unwrap(),
+            Entry::parse("10-11 DEF", &date(2024, 2, 13)).unwrap(),
+            Entry::parse("9-10 ABC", &date(2024, 2, 14)).unwrap(),
+        ]);
+        assert_eq!(expected, log);
+    }
+
+    #[test]
+    fn parse_nested_date_headings() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13
+            * 9-10 ABC
+            ### 2024-02-14
+            * 9-10 DEF
+            ### Back to the outer date
+            * 10-11 GHI
+        "});
+        let expected = Log(vec![
+            Entry::parse("9-10 ABC", &date(2024, 2, 13)).unwrap(),
+            Entry::parse("10-11 GHI", &date(2024, 2, 13)).unwrap(),
+            Entry::parse("9-10 DEF", &date(2024, 2, 14)).unwrap(),
+        ]);
+        assert_eq!(expected, log);
+    }
+
+    #[test]
+    fn parse_nested_entries() {
+        let input = indoc::indoc! {"
+            ## 2024-02-13
+            * 9-10 ABC
+              * 10-11 DEF
+            > * 11-12 GHI
+            >   * 12-13 JKL
+        "};
+        let result = |depth| {
+            let context = Context {
+                depth,
+                ..Default::default()
+            };
+            Log::parse(input, &context).unwrap().0.len()
+        };
+        assert_eq!(1, result(0));
+        assert_eq!(3, result(1));
+        assert_eq!(4, result(2));
+    }
+
+    #[test]
+    fn parse_weekday_headings() {
+        let context = Context::from_path(std::path::Path::new("2024-w07.md"));
+        let (log, warnings) = Log::parse_with_warnings(
+            indoc::indoc! {"
+                ## Monday
+                * 9-10 ABC
+                ## Tue (busy day)
+                * 9-10 DEF
+            "},
+            &context,
+        )
+        .unwrap();
+        let expected = Log(vec![
+            Entry::parse("9-10 ABC", &date(2024, 2, 12)).unwrap(),
+            Entry::parse("9-10 DEF", &date(2024, 2, 13)).unwrap(),
+        ]);
+        assert_eq!(expected, log);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn parse_weekday_heading_without_range() {
+        let (log, warnings) = Log::parse_with_warnings(
+            indoc::indoc! {"
+                ## Monday
+                * 9-10 ABC
+            "},
+            &Context::default(),
+        )
+        .unwrap();
+        assert!(log.0.is_empty());
+        assert_eq!(1, warnings.len());
+        assert_eq!(1, warnings[0].line);
+    }
+
+    #[test]
+    fn parse_date_outside_range() {
+        let context = Context::from_path(std::path::Path::new("2024-w07.md"));
+        let (log, warnings) = Log::parse_with_warnings(
+            indoc::indoc! {"
+                ## 2024-02-13
+                * 9-10 ABC
+                ## 2024-02-20
+                * 9-10 DEF
+            "},
+            &context,
+        )
+        .unwrap();
+        assert_eq!(2, log.0.len());
+        assert_eq!(
+            vec![Warning {
+                line: 3,
+                message: "2024-02-20 is outside of the date range 2024-02-12..2024-02-18".into()
+            }],
+            warnings
+        );
+    }
+
+    #[test]
+    fn within() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-11
+            * 9-10 ABC
+            ## 2024-02-13
+            * 9-10 DEF
+            ## 2024-02-19
+            * 9-10 GHI
+        "});
+        let range = DateRange(date(2024, 2, 12), date(2024, 2, 18));
+        let expected = Log(vec![Entry::parse("9-10 DEF", &date(2024, 2, 13)).unwrap()]);
+        assert_eq!(expected, log.within(&range));
+    }
+
+    #[test]
+    fn queries() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-13
+            * 9-10 ABC
+            * 10-11 DEF
+            ## 2024-02-14
+            * 9-10 ABC
+        "});
+        assert_eq!(3, log.len());
+        assert_eq!(2, log.for_project("ABC").len());
+        assert_eq!(1, log.on(&date(2024, 2, 14)).len());
+        assert!(log.for_project("GHI").is_empty());
+        let range = DateRange(date(2024, 2, 13), date(2024, 2, 14));
+        assert_eq!(Some(range), log.range());
+        assert_eq!(None, Log::new().range());
+        let long = log.filter(|entry| entry.duration() > chrono::TimeDelta::minutes(30));
+        assert_eq!(log, long);
+    }
+
+    #[test]
+    fn collect_in_order() {
+        let abc = Entry::parse("9-10 ABC", &date(2024, 2, 14)).unwrap();
+        let def = Entry::parse("9-10 DEF", &date(2024, 2, 13)).unwrap();
+        let mut log = [abc.clone()].into_iter().collect::<Log>();
+        log.extend([def.clone()]);
+        assert_eq!(vec![def, abc], log.into_entries());
+    }
+
     #[test]
     fn by_date_empty() {
-        let log = Log::parse("");
+        let log = parse("");
         let report = Log::by_date(&log);
         assert!(report.is_empty());
     }
 
     #[test]
     fn by_date_one_date() {
-        let log = Log::parse(indoc::indoc! {"
+        let log = parse(indoc::indoc! {"
             ## 2024-02-13
             * 9-10 ABC
             * 10-11 DEF
@@ -328,7 +680,7 @@ mod tests {
 
     #[test]
     fn by_date_multiple_dates() {
-        let log = Log::parse(indoc::indoc! {"
+        let log = parse(indoc::indoc! {"
             ## 2024-02-13
             * 9-10 ABC
             * 10-11 DEF
@@ -352,14 +704,14 @@ mod tests {
 
     #[test]
     fn by_project_empty() {
-        let log = Log::parse("");
+        let log = parse("");
         let report = Log::by_project(&log);
         assert!(report.is_empty());
     }
 
     #[test]
     fn by_project_one_date() {
-        let log = Log::parse(indoc::indoc! {"
+        let log = parse(indoc::indoc! {"
             ## 2024-02-13
             * 9-10 ABC
             * 10-11 DEF
@@ -378,7 +730,7 @@ mod tests {
 
     #[test]
     fn by_project_multiple_dates() {
-        let log = Log::parse(indoc::indoc! {"
+        let log = parse(indoc::indoc! {"
             ## 2024-02-13
             * 9-10 ABC
             * 10-11 DEF
@@ -402,7 +754,7 @@ mod tests {
 
     #[test]
     fn sum_duration_empty() {
-        let log = Log::parse("");
+        let log = parse("");
         let duration = log.sum_duration();
         let expected = chrono::TimeDelta::zero();
         assert_eq!(expected, duration);
@@ -410,7 +762,7 @@ mod tests {
 
     #[test]
     fn sum_duration_entries() {
-        let log = Log::parse(indoc::indoc! {"
+        let log = parse(indoc::indoc! {"
             ## 2024-02-13
             * 9-10 ABC
             * 10-11 DEF
@@ -423,4 +775,22 @@ mod tests {
         let expected = chrono::TimeDelta::hours(5);
         assert_eq!(expected, duration);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let log = parse(indoc::indoc! {"
+            ## 2024-02-14
+            * all day LEAVE
+            ## 2024-02-13
+            * 9-10 ABC
+        "});
+        let json = serde_json::to_string(&log).unwrap();
+        assert!(json.starts_with(r#"[{"date":"2024-02-13""#));
+        assert_eq!(log, serde_json::from_str(&json).unwrap());
+        let mut entries = serde_json::from_str::<Vec<serde_json::Value>>(&json).unwrap();
+        entries.reverse();
+        let reversed = serde_json::to_string(&entries).unwrap();
+        assert_eq!(log, serde_json::from_str(&reversed).unwrap());
+    }
 }
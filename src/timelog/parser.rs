@@ -0,0 +1,131 @@
+use super::{Context, Entry, Time, Warning};
+use crate::Error;
+use markdown::mdast;
+use std::sync::LazyLock;
+
+/// A day off marked in a date heading, like `## 2024-02-14 (vacation)`.
+static LEAVE_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"(?i)\((?<reason>vacation|holiday|leave|sick(?: leave)?|day off|pto)\)")
+        .unwrap()
+});
+
+/// Walks the markdown tree, keeping track of which date heading applies to each list. The line
+/// scanner reports headings and list items to it the same way.
+pub(super) struct Parser<'a> {
+    context: &'a Context,
+    /// Date headings currently in scope, as `(heading depth, date)` with the innermost last.
+    headings: Vec<(u8, chrono::NaiveDate)>,
+    entries: Vec<Entry>,
+    warnings: Vec<Warning>,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(context: &'a Context) -> Self {
+        Self {
+            context,
+            headings: vec![],
+            entries: vec![],
+            warnings: vec![],
+        }
+    }
+
+    pub fn parse(mut self, input: &str) -> Result<(Vec<Entry>, Vec<Warning>), Error> {
+        let ast = parse_md(input)?;
+        self.visit(ast.children().map_or(&[], Vec::as_slice), 0);
+        Ok(self.finish())
+    }
+
+    pub fn context(&self) -> &Context {
+        self.context
+    }
+
+    pub fn finish(self) -> (Vec<Entry>, Vec<Warning>) {
+        (self.entries, self.warnings)
+    }
+
+    fn date(&self) -> Option<&chrono::NaiveDate> {
+        self.headings.last().map(|(_, date)| date)
+    }
+
+    /// Visits sibling nodes at the given level of nesting, where the document's top-level nodes
+    /// are at 0. Block quotes and sub-lists are only entered up to the context's `depth`.
+    fn visit(&mut self, nodes: &[mdast::Node], nesting: usize) {
+        for node in nodes.iter() {
+            match node {
+                mdast::Node::Heading(heading) => {
+                    let line = node.position().map_or(0, |p| p.start.line);
+                    self.heading(heading.depth, line, &node.to_string());
+                }
+                mdast::Node::List(mdast::List { children, .. }) => {
+                    for list_item in children.iter() {
+                        self.list_item(list_item.children().unwrap(), nesting);
+                    }
+                }
+                mdast::Node::Paragraph(_) => {}
+                _ if nesting < self.context.depth => {
+                    if let Some(children) = node.children() {
+                        self.visit(children, nesting + 1);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn list_item(&mut self, children: &[mdast::Node], nesting: usize) {
+        let Some((item_text, rest)) = children.split_first() else {
+            return;
+        };
+        self.item(&item_text.to_string());
+        if nesting < self.context.depth {
+            self.visit(rest, nesting + 1);
+        }
+    }
+
+    /// The text of the first block in a list item, which is an entry if it has the right syntax.
+    pub fn item(&mut self, text: &str) {
+        if let Some(date) = self.date() {
+            if let Some(entry) = Entry::parse(text, date) {
+                self.entries.push(entry);
+            }
+        }
+    }
+
+    /// A heading ends the scope of any date heading at the same or a higher level (lower depth).
+    pub fn heading(&mut self, depth: u8, line: usize, text: &str) {
+        self.headings.retain(|(d, _)| *d < depth);
+        let heading = self.context.headings.parse(text, self.context);
+        if let Some(message) = heading.warning {
+            self.warnings.push(Warning { line, message });
+        }
+        let Some(date) = heading.date else {
+            return;
+        };
+        if let Some(range) = &self.context.range {
+            if !range.contains(&date) {
+                self.warnings.push(Warning {
+                    line,
+                    message: format!("{} is outside of the date range {}", date, range),
+                });
+            }
+        }
+        if let Some(cap) = LEAVE_RE.captures(text) {
+            self.entries.push(Entry {
+                date,
+                time: Time::AllDay,
+                project: cap["reason"].to_owned(),
+                notes: None,
+            });
+        }
+        self.headings.push((depth, date));
+    }
+}
+
+/// Parses markdown, which only fails for syntax extensions that aren't enabled, like MDX.
+pub(super) fn parse_md(input: &str) -> Result<mdast::Node, Error> {
+    let opts = &markdown::ParseOptions::default();
+    markdown::to_mdast(input, opts).map_err(|message| Error::Markdown {
+        path: None,
+        message: message.to_string(),
+    })
+}
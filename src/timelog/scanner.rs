@@ -0,0 +1,434 @@
+use super::parser::{parse_md, Parser};
+use std::sync::LazyLock;
+
+static ATX_HEADING_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"^(?<level>#{1,6})(?:[ \t]+(?<text>.*)|$)").unwrap());
+
+static SETEXT_UNDERLINE_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"^(?:(?<h1>=+)|-+)[ \t]*$").unwrap());
+
+static THEMATIC_BREAK_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"^(?:(?:\*[ \t]*){3,}|(?:-[ \t]*){3,}|(?:_[ \t]*){3,})$").unwrap()
+});
+
+static FENCE_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"^(?:`{3,}[^`]*|~{3,}.*)$").unwrap());
+
+static LIST_ITEM_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"^(?:[-*+]|(?<number>\d{1,9})[.)])(?<space>[ \t]+|$)").unwrap()
+});
+
+/// Characters that may start inline markup, which the scanner leaves to the markdown parser.
+static MARKUP: [char; 8] = ['\\', '`', '*', '_', '[', ']', '<', '&'];
+
+/// Reads headings and list items line by line, instead of building a markdown tree first. It
+/// follows CommonMark for block quotes, lists, headings, thematic breaks and code, which is
+/// what logs are made of, and passes what it finds to the parser.
+pub(super) struct Scanner<'a> {
+    parser: Parser<'a>,
+    /// Whether to parse text that has inline markup as markdown, like the parser would.
+    compatible: bool,
+    /// The columns where the content of the list items that are still open starts, innermost
+    /// last.
+    lists: Vec<usize>,
+    /// How many block quotes the previous line was in.
+    quotes: usize,
+    paragraph: Option<Paragraph>,
+    /// The character and length of the fence of the code block the scanner is in, if any.
+    fence: Option<(char, usize)>,
+}
+
+struct Paragraph {
+    /// Whether the paragraph starts a list item, so it may be an entry.
+    item: bool,
+    nesting: usize,
+    /// The column where the content of the list item the paragraph is in starts, if any.
+    column: usize,
+    lines: Vec<String>,
+}
+
+/// What a line starts, apart from its indentation.
+enum Block<'a> {
+    Heading(u8, &'a str),
+    Fence(char, usize),
+    ThematicBreak,
+    Item {
+        /// From the start of the marker to the column where the item's content starts.
+        width: usize,
+        /// Whether the content is indented code rather than a paragraph.
+        code: bool,
+        /// The number of an ordered list item.
+        number: Option<u32>,
+        content: &'a str,
+    },
+    Text(&'a str),
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(parser: Parser<'a>, compatible: bool) -> Self {
+        Self {
+            parser,
+            compatible,
+            lists: vec![],
+            quotes: 0,
+            paragraph: None,
+            fence: None,
+        }
+    }
+
+    pub fn scan(mut self, input: &str) -> Parser<'a> {
+        for (index, line) in input.lines().enumerate() {
+            self.line(index + 1, line);
+        }
+        self.close_paragraph();
+        self.parser
+    }
+
+    fn line(&mut self, number: usize, line: &str) {
+        let (quotes, line) = strip_quotes(line);
+        let text = line.trim_start();
+        if let Some((fence, length)) = self.fence {
+            let closing = text.trim_end();
+            if closing.len() >= length && closing.chars().all(|c| c == fence) {
+                self.fence = None;
+            }
+            return;
+        }
+        if let Some(paragraph) = self.paragraph.as_mut().filter(|_| quotes < self.quotes) {
+            // A lazy continuation of a paragraph in a block quote, without all of its markers.
+            let lazy = indentation(line) >= 4 || matches!(block(text), Block::Text(_));
+            if lazy && !text.is_empty() {
+                paragraph.lines.push(text.to_owned());
+                return;
+            }
+        }
+        if quotes != self.quotes {
+            self.close_paragraph();
+            self.lists.clear();
+            self.quotes = quotes;
+        }
+        if text.is_empty() {
+            self.close_paragraph();
+            return;
+        }
+        let indent = indentation(line);
+        let container = self
+            .lists
+            .iter()
+            .rev()
+            .find(|column| **column <= indent)
+            .copied()
+            .unwrap_or(0);
+        if let Some(paragraph) = &mut self.paragraph {
+            if indent - container < 4 {
+                if let Some(cap) = SETEXT_UNDERLINE_RE.captures(text) {
+                    let level = if cap.name("h1").is_some() { 1 } else { 2 };
+                    if !paragraph.item {
+                        self.setext_heading(number, level, text);
+                        return;
+                    }
+                    // Underlines within a list item make its first paragraph a heading, which
+                    // has the same text.
+                    if container > 0 && Some(&container) == self.lists.last() {
+                        self.close_paragraph();
+                        return;
+                    }
+                }
+            }
+            let interrupts = match block(text) {
+                _ if indent - container >= 4 => false,
+                Block::Text(_) => false,
+                // Only non-empty lists starting at 1 can interrupt a paragraph that isn't in one
+                // of their items.
+                Block::Item {
+                    number, content, ..
+                } if !paragraph.item && indent >= paragraph.column => {
+                    !content.is_empty() && number.unwrap_or(1) == 1
+                }
+                _ => true,
+            };
+            if !interrupts {
+                paragraph.lines.push(text.to_owned());
+                return;
+            }
+            self.close_paragraph();
+        }
+        self.lists.retain(|column| *column <= indent);
+        if indent - container >= 4 {
+            // An indented code block.
+            return;
+        }
+        let nesting = self.quotes + self.lists.len();
+        match block(text) {
+            Block::Heading(level, content) => {
+                if nesting <= self.parser.context().depth {
+                    let text = match self.compatible && content.contains(MARKUP) {
+                        true => markdown_text(text),
+                        false => heading_text(content).to_owned(),
+                    };
+                    self.parser.heading(level, number, &text);
+                }
+            }
+            Block::Fence(fence, length) => self.fence = Some((fence, length)),
+            Block::ThematicBreak => {}
+            Block::Item {
+                width,
+                code,
+                content,
+                ..
+            } => {
+                self.lists.push(indent + width);
+                if code {
+                    if nesting <= self.parser.context().depth {
+                        self.parser.item(content);
+                    }
+                } else if !content.is_empty() {
+                    self.paragraph = Some(Paragraph {
+                        item: true,
+                        nesting,
+                        column: indent + width,
+                        lines: vec![content.to_owned()],
+                    });
+                }
+            }
+            Block::Text(content) => {
+                self.paragraph = Some(Paragraph {
+                    item: false,
+                    nesting,
+                    column: self.lists.last().copied().unwrap_or(0),
+                    lines: vec![content.to_owned()],
+                })
+            }
+        }
+    }
+
+    /// Ends the current paragraph, which is an entry if it starts a list item with one.
+    fn close_paragraph(&mut self) {
+        let Some(paragraph) = self.paragraph.take() else {
+            return;
+        };
+        if paragraph.item && paragraph.nesting <= self.parser.context().depth {
+            let text = match self.compatible && needs_markdown(&paragraph.lines) {
+                true => markdown_item_text(&paragraph.lines),
+                false => paragraph.lines.join("\n"),
+            };
+            self.parser.item(&text);
+        }
+    }
+
+    /// Makes the current paragraph a heading, for an underline of `===` or `---`.
+    fn setext_heading(&mut self, number: usize, level: u8, underline: &str) {
+        let Some(paragraph) = self.paragraph.take() else {
+            return;
+        };
+        if paragraph.nesting > self.parser.context().depth {
+            return;
+        }
+        let line = number - paragraph.lines.len();
+        let text = match self.compatible && needs_markdown(&paragraph.lines) {
+            true => markdown_text(&format!(
+                "{}\n{}",
+                continued(&paragraph.lines, "    "),
+                underline
+            )),
+            false => paragraph.lines.join("\n"),
+        };
+        self.parser.heading(level, line, &text);
+    }
+}
+
+/// Strips the markers of block quotes from a line, returning how many there were.
+fn strip_quotes(mut line: &str) -> (usize, &str) {
+    let mut quotes = 0;
+    loop {
+        let text = line.trim_start_matches(' ');
+        match text.strip_prefix('>') {
+            Some(rest) if line.len() - text.len() < 4 => {
+                line = rest.strip_prefix(' ').unwrap_or(rest);
+                quotes += 1;
+            }
+            _ => return (quotes, line),
+        }
+    }
+}
+
+/// The column where the text of a line starts, with tab stops of 4 columns.
+fn indentation(line: &str) -> usize {
+    let mut column = 0;
+    for c in line.chars() {
+        match c {
+            ' ' => column += 1,
+            '\t' => column += 4 - column % 4,
+            _ => break,
+        }
+    }
+    column
+}
+
+fn block(text: &str) -> Block<'_> {
+    if let Some(cap) = ATX_HEADING_RE.captures(text) {
+        let level = cap["level"].len() as u8;
+        return Block::Heading(level, cap.name("text").map_or("", |m| m.as_str()));
+    }
+    if FENCE_RE.is_match(text) {
+        let fence = text.chars().next().unwrap_or('`');
+        let length = text.chars().take_while(|c| *c == fence).count();
+        return Block::Fence(fence, length);
+    }
+    if THEMATIC_BREAK_RE.is_match(text) {
+        return Block::ThematicBreak;
+    }
+    if let Some(cap) = LIST_ITEM_RE.captures(text) {
+        let space = cap.name("space").unwrap();
+        let content = &text[space.end()..];
+        // Content after more than 4 spaces is code, indented by a space.
+        let code = !content.is_empty() && space.len() > 4;
+        let width = match content.is_empty() || code {
+            true => space.start() + 1,
+            false => space.end(),
+        };
+        return Block::Item {
+            width,
+            code,
+            number: cap.name("number").and_then(|m| m.as_str().parse().ok()),
+            content,
+        };
+    }
+    Block::Text(text)
+}
+
+/// The text of an ATX heading, without a closing sequence of `#`s.
+fn heading_text(content: &str) -> &str {
+    let content = content.trim();
+    let without = content.trim_end_matches('#');
+    match without.is_empty() || without.ends_with([' ', '\t']) {
+        true => without.trim_end(),
+        false => content,
+    }
+}
+
+/// Whether text may have inline markup, span lines, or start like a block that isn't a
+/// paragraph, so its text may differ from its source.
+fn needs_markdown(lines: &[String]) -> bool {
+    let [line] = lines else {
+        return true;
+    };
+    line.contains(MARKUP) || !line.starts_with(|c: char| c.is_alphanumeric()) || {
+        let digits = line.trim_start_matches(|c: char| c.is_ascii_digit());
+        digits.len() < line.len() && digits.starts_with(['.', ')'])
+    }
+}
+
+/// The text of the first block in markdown, like the parser reads it.
+fn markdown_text(input: &str) -> String {
+    parse_md(input)
+        .ok()
+        .and_then(|root| root.children()?.first().map(ToString::to_string))
+        .unwrap_or_default()
+}
+
+/// Joins the lines of a paragraph, indenting the lines after the first so none of them can start
+/// another block when they're parsed again.
+fn continued(lines: &[String], indent: &str) -> String {
+    lines.join(&format!("\n{}", indent))
+}
+
+/// The text of the first block of a list item with the given lines, like the parser reads it.
+fn markdown_item_text(lines: &[String]) -> String {
+    parse_md(&format!("- {}", continued(lines, "      ")))
+        .ok()
+        .and_then(|root| {
+            let list = root.children()?.first()?;
+            let item = list.children()?.first()?;
+            item.children()?.first().map(ToString::to_string)
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Context, Log, Reader};
+
+    /// Parses with the markdown parser and the compatible scanner, and checks they find the same
+    /// entries, with the same notes, and warnings. Returns how many entries there are.
+    fn compare(input: &str, depth: usize) -> usize {
+        let parse = |reader| {
+            let context = Context {
+                depth,
+                reader,
+                ..Context::from_path(std::path::Path::new("2024-w07.md"))
+            };
+            let (log, warnings) = Log::parse_with_warnings(input, &context).unwrap();
+            let entries = log.iter().map(ToString::to_string).collect::<Vec<_>>();
+            (entries, warnings)
+        };
+        let expected = parse(Reader::Markdown);
+        assert_eq!(expected, parse(Reader::Lines));
+        expected.0.len()
+    }
+
+    #[test]
+    fn compatible_with_markdown() {
+        for file in [
+            "bench_short",
+            "bench_long",
+            "bench_long_scrambled",
+            "2024-w07-example",
+        ] {
+            let path = format!("{}/resources/{}.md", env!("CARGO_MANIFEST_DIR"), file);
+            let input = std::fs::read_to_string(path).unwrap();
+            assert!(compare(&input, 0) > 0);
+        }
+    }
+
+    #[test]
+    fn compatible_with_markdown_syntax() {
+        let input = indoc::indoc! {"
+            Tuesday
+            =======
+
+            * 9-10 ABC
+            * 10-11 **DEF** with *markup*
+            * 11-12 GHI
+            continued lazily
+            1. 12-13 JKL
+               - 13-14 MNO nested
+            2) 14-15 PQR
+
+            ```
+            - 15-16 STU in code
+            ## 2024-02-15
+            ```
+
+                - 16-17 VWX indented code
+            ***
+            > - 17-18 YZA quoted
+            > > ## 2024-02-16
+            > > - 9-10 BCD
+
+            ## Wednesday ##
+            -   10-11 EFG
+            -      11-12 HIJ
+            para
+            - 12-13 KLM
+            2. 13-14 NOP
+            ### 2024-02-14 (vacation) ###
+            - 14-15 QRS \\
+              escaped
+        "};
+        assert_eq!(11, compare(input, 0));
+        assert_eq!(13, compare(input, 1));
+        assert_eq!(14, compare(input, 2));
+    }
+
+    #[test]
+    fn plain_lines() {
+        let context = Context {
+            reader: Reader::PlainLines,
+            ..Context::default()
+        };
+        let input = "## 2024-02-13\n- 9-10 ABC\n- 10-11 **DEF**\n";
+        let log = Log::parse(input, &context).unwrap();
+        assert_eq!(1, log.len());
+    }
+}
@@ -1,9 +1,139 @@
+use regex::Regex;
+use serde::Deserialize;
+use std::{str::FromStr, sync::LazyLock};
+
+/// A year, optionally followed by a week, or a month and day.
+static RANGE: &str = r"
+    (?<year>\d{4})
+    (?:
+        -?w(?<week>\d{1,2})
+        |-(?<month>\d{1,2})(?:-(?<day>\d{1,2}))?
+    )?";
+
+/// A range anywhere in a file name, but not within a longer number.
+static NAME_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"(?xi)(?:^|[^\d]){}(?:$|[^\d])", RANGE)).unwrap());
+
+/// Just a range, as given on the command line.
+static RANGE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"(?xi)^{}$", RANGE)).unwrap());
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
 pub struct DateRange(pub chrono::NaiveDate, pub chrono::NaiveDate);
 
 impl DateRange {
+    pub fn day(date: chrono::NaiveDate) -> Self {
+        Self(date, date)
+    }
+
+    pub fn iso_week(year: i32, week: u32) -> Option<Self> {
+        let monday = chrono::NaiveDate::from_isoywd_opt(year, week, chrono::Weekday::Mon)?;
+        let sunday = chrono::NaiveDate::from_isoywd_opt(year, week, chrono::Weekday::Sun)?;
+        Some(Self(monday, sunday))
+    }
+
+    pub fn month(year: i32, month: u32) -> Option<Self> {
+        let first = chrono::NaiveDate::from_ymd_opt(year, month, 1)?;
+        let last = first.checked_add_months(chrono::Months::new(1))? - ONE_DAY;
+        Some(Self(first, last))
+    }
+
+    pub fn year(year: i32) -> Option<Self> {
+        let first = chrono::NaiveDate::from_ymd_opt(year, 1, 1)?;
+        let last = chrono::NaiveDate::from_ymd_opt(year, 12, 31)?;
+        Some(Self(first, last))
+    }
+
+    /// Finds the date range implied by a file name like `2024-w07.md`, `2024-02.md` or
+    /// `2024-02-13.md`. Any other text around the date is ignored.
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        NAME_RE
+            .captures_iter(name)
+            .find_map(|cap| range_from_captures(&cap))
+    }
+
     pub fn contains(&self, reference: &chrono::NaiveDate) -> bool {
         reference >= &self.0 && reference <= &self.1
     }
+
+    /// Whether the two ranges have any dates in common.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.0 <= other.1 && other.0 <= self.1
+    }
+}
+
+fn range_from_captures(cap: &regex::Captures) -> Option<DateRange> {
+    let part = |name: &str| cap.name(name).and_then(|m| m.as_str().parse::<u32>().ok());
+    let year = cap["year"].parse::<i32>().ok()?;
+    match (part("week"), part("month"), part("day")) {
+        (Some(week), _, _) => DateRange::iso_week(year, week),
+        (None, Some(month), Some(day)) => {
+            chrono::NaiveDate::from_ymd_opt(year, month, day).map(DateRange::day)
+        }
+        (None, Some(month), None) => DateRange::month(year, month),
+        (None, None, _) => DateRange::year(year),
+    }
+}
+
+impl FromStr for DateRange {
+    type Err = ParseDateRangeError;
+
+    /// Parses an absolute date range: a date (`2024-02-13`), an ISO week (`2024-w07`), a month
+    /// (`2024-02`), a year (`2024`), or the first and last date (`2024-02-12..2024-02-18`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let err = || ParseDateRangeError {
+            input: s.to_owned(),
+        };
+        if let Some((from, until)) = s.split_once("..") {
+            let from = from.trim().parse().map_err(|_| err())?;
+            let until = until.trim().parse().map_err(|_| err())?;
+            return match from <= until {
+                true => Ok(Self(from, until)),
+                false => Err(err()),
+            };
+        }
+        let cap = RANGE_RE.captures(s).ok_or_else(err)?;
+        range_from_captures(&cap).ok_or_else(err)
+    }
+}
+
+impl TryFrom<String> for DateRange {
+    type Error = ParseDateRangeError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseDateRangeError {
+    pub input: String,
+}
+impl std::error::Error for ParseDateRangeError {}
+impl std::fmt::Display for ParseDateRangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid date range {}, expected a day, week, month or year like 2024-02-13, 2024-w07, 2024-02 or 2024, or dates like 2024-02-12..2024-02-18",
+            self.input
+        )
+    }
+}
+
+impl std::fmt::Display for DateRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}..{}", self.0, self.1)
+    }
+}
+
+/// Date ranges are serialized like they're displayed, as `2024-02-12..2024-02-18`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for DateRange {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
 }
 
 static ONE_DAY: chrono::Days = chrono::Days::new(1);
@@ -11,12 +141,16 @@ static ONE_DAY: chrono::Days = chrono::Days::new(1);
 impl Iterator for DateRange {
     type Item = chrono::NaiveDate;
     fn next(&mut self) -> Option<Self::Item> {
-        if self.0 <= self.1 {
-            let next = self.0 + ONE_DAY;
-            Some(std::mem::replace(&mut self.0, next))
-        } else {
-            None
+        if self.0 > self.1 {
+            return None;
+        }
+        let date = self.0;
+        match date.checked_add_days(ONE_DAY) {
+            Some(next) => self.0 = next,
+            // The last date there is, so end here.
+            None => self.1 = date.pred_opt()?,
         }
+        Some(date)
     }
 }
 
@@ -38,6 +172,15 @@ mod tests {
         assert!(!range.contains(&date(2024, 2, 17)));
     }
 
+    #[test]
+    fn overlaps() {
+        let range = DateRange(date(2024, 2, 12), date(2024, 2, 18));
+        assert!(range.overlaps(&DateRange(date(2024, 2, 1), date(2024, 2, 29))));
+        assert!(range.overlaps(&DateRange(date(2024, 2, 18), date(2024, 2, 25))));
+        assert!(!range.overlaps(&DateRange(date(2024, 2, 19), date(2024, 2, 25))));
+        assert!(!range.overlaps(&DateRange(date(2024, 1, 1), date(2024, 2, 11))));
+    }
+
     #[test]
     fn iterator() {
         let range = DateRange(date(2024, 2, 12), date(2024, 2, 16));
@@ -51,4 +194,81 @@ mod tests {
         ];
         assert_eq!(expected, result);
     }
+
+    #[test]
+    fn from_file_name_week() {
+        let expected = Some(DateRange(date(2024, 2, 12), date(2024, 2, 18)));
+        assert_eq!(expected, DateRange::from_file_name("2024-w07"));
+        assert_eq!(expected, DateRange::from_file_name("2024-W07"));
+        assert_eq!(expected, DateRange::from_file_name("2024w7"));
+        assert_eq!(expected, DateRange::from_file_name("log 2024-w07 (draft)"));
+    }
+
+    #[test]
+    fn from_file_name_week_across_years() {
+        let expected = Some(DateRange(date(2024, 12, 30), date(2025, 1, 5)));
+        assert_eq!(expected, DateRange::from_file_name("2025-w01"));
+    }
+
+    #[test]
+    fn from_file_name_month() {
+        let expected = Some(DateRange(date(2024, 2, 1), date(2024, 2, 29)));
+        assert_eq!(expected, DateRange::from_file_name("2024-02"));
+    }
+
+    #[test]
+    fn from_file_name_day() {
+        let expected = Some(DateRange(date(2024, 2, 13), date(2024, 2, 13)));
+        assert_eq!(expected, DateRange::from_file_name("2024-02-13"));
+    }
+
+    #[test]
+    fn from_file_name_invalid() {
+        assert_eq!(None, DateRange::from_file_name("notes"));
+        assert_eq!(None, DateRange::from_file_name("2024-w54"));
+        assert_eq!(None, DateRange::from_file_name("2024-13"));
+        assert_eq!(None, DateRange::from_file_name("20240213"));
+    }
+
+    #[test]
+    fn from_str() {
+        let expected = Ok(DateRange(date(2024, 2, 12), date(2024, 2, 18)));
+        assert_eq!(expected, "2024-w07".parse::<DateRange>());
+        let expected = Ok(DateRange(date(2024, 1, 1), date(2024, 12, 31)));
+        assert_eq!(expected, "2024".parse::<DateRange>());
+        assert!("2024-w07.md".parse::<DateRange>().is_err());
+        assert!("week 2024-w07".parse::<DateRange>().is_err());
+        assert!("x2024-w07".parse::<DateRange>().is_err());
+        assert!("a2024b".parse::<DateRange>().is_err());
+        assert!("2024-w07 ".parse::<DateRange>().is_ok());
+    }
+
+    #[test]
+    fn from_str_dates() {
+        let range = DateRange(date(2024, 2, 12), date(2024, 2, 18));
+        assert_eq!(Ok(range.clone()), range.to_string().parse());
+        assert_eq!(Ok(range), "2024-02-12 .. 2024-02-18".parse());
+        assert!("2024-02-18..2024-02-12".parse::<DateRange>().is_err());
+        assert!("2024-02..2024-03".parse::<DateRange>().is_err());
+    }
+
+    #[test]
+    fn iterate_up_to_last_date() {
+        let last = chrono::NaiveDate::MAX;
+        let range = DateRange(last.pred_opt().unwrap(), last);
+        assert_eq!(
+            vec![last.pred_opt().unwrap(), last],
+            range.collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let range = DateRange(date(2024, 2, 12), date(2024, 2, 18));
+        let json = serde_json::to_string(&range).unwrap();
+        assert_eq!(r#""2024-02-12..2024-02-18""#, json);
+        assert_eq!(range, serde_json::from_str(&json).unwrap());
+        assert_eq!(range, serde_json::from_str(r#""2024-w07""#).unwrap());
+    }
 }
@@ -0,0 +1,36 @@
+//! Durations as whole minutes, for `#[serde(with = "minutes")]`. Seconds are truncated.
+
+use serde::{Deserialize, Deserializer, Serializer};
+
+pub fn serialize<S: Serializer>(duration: &chrono::TimeDelta, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_i64(duration.num_minutes())
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<chrono::TimeDelta, D::Error> {
+    let minutes = i64::deserialize(d)?;
+    chrono::TimeDelta::try_minutes(minutes)
+        .ok_or_else(|| serde::de::Error::custom(format!("{} minutes is out of range", minutes)))
+}
+
+#[cfg(feature = "serde")]
+pub mod option {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<chrono::TimeDelta>,
+        s: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => super::serialize(duration, s),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        d: D,
+    ) -> Result<Option<chrono::TimeDelta>, D::Error> {
+        #[derive(Deserialize)]
+        struct Minutes(#[serde(with = "super")] chrono::TimeDelta);
+        Ok(Option::<Minutes>::deserialize(d)?.map(|Minutes(duration)| duration))
+    }
+}
@@ -1,13 +1,17 @@
 mod daterange;
+#[cfg(any(feature = "serde", feature = "cache"))]
+pub(crate) mod minutes;
+mod parallel;
 
-pub use daterange::DateRange;
+pub use daterange::{DateRange, ParseDateRangeError};
 use indexmap::map::IndexMap;
+pub use parallel::{default_threads, map_parallel};
 
 pub fn pad_dates<T: std::default::Default>(
     map: &mut IndexMap<chrono::NaiveDate, T>,
     range: Option<DateRange>,
 ) {
-    if map.is_empty() {
+    if map.is_empty() && range.is_none() {
         return;
     }
     let range = range.unwrap_or_else(|| {
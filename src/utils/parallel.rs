@@ -0,0 +1,69 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Calls `f` on each item on up to `threads` threads, returning the results in the order of the
+/// items. Threads take the next item as soon as they're done with one, so a few large items don't
+/// hold up the rest.
+pub fn map_parallel<T, R, F>(items: &[T], threads: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = threads.min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+    let next = AtomicUsize::new(0);
+    let mut results = thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = vec![];
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        match items.get(index) {
+                            Some(item) => results.push((index, f(item))),
+                            None => return results,
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|err| std::panic::resume_unwind(err))
+            })
+            .collect::<Vec<_>>()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// The number of threads to use when none is configured: one per CPU.
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |threads| threads.get())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_order() {
+        let items = (0..100).collect::<Vec<u64>>();
+        let expected = items.iter().map(|item| item * item).collect::<Vec<_>>();
+        for threads in [0, 1, 3, 8, 200] {
+            let squares = map_parallel(&items, threads, |item| {
+                // Later items finish first, to shuffle the order they're done in.
+                thread::sleep(std::time::Duration::from_micros(100 - item));
+                item * item
+            });
+            assert_eq!(expected, squares);
+        }
+        assert!(map_parallel(&[] as &[u64], 4, |item| *item).is_empty());
+    }
+}